@@ -61,7 +61,7 @@ pub async fn process_photos(
     Ok(qr_id)
 }
 
-fn resize_photo(image: &DynamicImage, max_width: u32, max_height: u32) -> DynamicImage {
+pub fn resize_photo(image: &DynamicImage, max_width: u32, max_height: u32) -> DynamicImage {
     let (width, height) = image.dimensions();
 
     let scale = f32::min(
@@ -1,7 +1,12 @@
-use super::meilisearch::add_items;
+use super::meilisearch::{add_items, delete_item};
 use crate::{
     AppError, AppState, WebsitePath,
-    api::web::swap::cdc::{convert_cdc_item, handle_item_deletion},
+    api::microservices::database::schema::columns::boiler_swap::items,
+    api::web::swap::{
+        alerts::spawn_alert_task,
+        catalog::read_catalog,
+        cdc::{convert_cdc_item, handle_item_deletion},
+    },
     metrics::{decr_metric, incr_metric},
     tables,
 };
@@ -9,7 +14,6 @@ use anyhow::{Error as anyhowError, Result as anyResult};
 use async_trait::async_trait;
 use chrono::{Duration as chronoDuration, NaiveDate};
 use futures_util::future::RemoteHandle;
-use meilisearch_sdk::client::*;
 use once_cell::sync::Lazy;
 use scylla_cdc::{
     checkpoints::TableBackedCheckpointSaver,
@@ -43,6 +47,7 @@ pub struct MeiliConsumer {
     pub redis_metric: String,
     pub redis_deletion_name: String,
     pub redis_metric_prefix: String,
+    pub pending_update: bool,
 }
 
 impl MeiliConsumer {
@@ -63,6 +68,7 @@ impl MeiliConsumer {
             redis_metric,
             redis_deletion_name,
             redis_metric_prefix,
+            pending_update: false,
         }
     }
 }
@@ -85,12 +91,14 @@ impl Consumer for MeiliConsumer {
 
                 choose_addition(
                     &data,
-                    self.state.meili_client.clone(),
+                    self.state.clone(),
                     &self.meili_index,
                     &self.scylla_id_name,
                     &self.website_path,
                 )
                 .await?;
+
+                choose_alerts(&data, self.state.clone(), &self.website_path);
             }
             OperationType::RowDelete
             | OperationType::PartitionDelete
@@ -119,6 +127,22 @@ impl Consumer for MeiliConsumer {
                 )
                 .await?;
             }
+            OperationType::RowUpdate => {
+                // The delta only carries the changed columns, so wait for the post-image
+                self.pending_update = true;
+            }
+            OperationType::PostImage if self.pending_update => {
+                self.pending_update = false;
+
+                choose_addition(
+                    &data,
+                    self.state.clone(),
+                    &self.meili_index,
+                    &self.scylla_id_name,
+                    &self.website_path,
+                )
+                .await?;
+            }
             _ => {}
         }
         Ok(())
@@ -127,17 +151,27 @@ impl Consumer for MeiliConsumer {
 
 async fn choose_addition(
     data: &CDCRow<'_>,
-    meili_client: Arc<Client>,
+    state: Arc<AppState>,
     meili_index: &str,
     scylla_id_name: &str,
     website_path: &WebsitePath,
 ) -> anyResult<()> {
     match website_path {
+        WebsitePath::BoilerSwap if try_get_cdc_bool(data, items::HIDDEN) == Some(true) => {
+            delete_item(
+                state.meili_client.clone(),
+                meili_index,
+                get_cdc_id(data, scylla_id_name),
+            )
+            .await
+        }
         WebsitePath::BoilerSwap => {
+            let item = convert_cdc_item(data, &read_catalog(&state.catalog));
+
             add_items(
-                meili_client,
+                state.meili_client.clone(),
                 meili_index,
-                &[convert_cdc_item(data)],
+                &[item],
                 scylla_id_name,
             )
             .await
@@ -147,6 +181,18 @@ async fn choose_addition(
     }
 }
 
+fn choose_alerts(data: &CDCRow<'_>, state: Arc<AppState>, website_path: &WebsitePath) {
+    match website_path {
+        WebsitePath::BoilerSwap => spawn_alert_task(
+            state.clone(),
+            convert_cdc_item(data, &read_catalog(&state.catalog)),
+            try_get_cdc_text(data, items::OWNER),
+        ),
+        WebsitePath::Photos => panic!("Photos not implemented"),
+        WebsitePath::Home => panic!("Home not implemented"),
+    }
+}
+
 async fn choose_deletion(
     data: &CDCRow<'_>,
     state: Arc<AppState>,
@@ -254,8 +300,15 @@ pub fn get_cdc_tinyint(data: &CDCRow<'_>, column: &str) -> i8 {
         .expect("Missing tinyint attribute")
 }
 
-pub fn get_cdc_u8(data: &CDCRow<'_>, column: &str) -> u8 {
-    convert_i8_to_u8(&get_cdc_tinyint(data, column))
+pub fn try_get_cdc_u8(data: &CDCRow<'_>, column: &str) -> Option<u8> {
+    data.get_value(column)
+        .as_ref()
+        .and_then(|v| v.as_tinyint())
+        .map(|v| convert_i8_to_u8(&v))
+}
+
+pub fn try_get_cdc_bool(data: &CDCRow<'_>, column: &str) -> Option<bool> {
+    data.get_value(column).as_ref().and_then(|v| v.as_boolean())
 }
 
 pub fn get_cdc_text(data: &CDCRow<'_>, column: &str) -> String {
@@ -266,6 +319,21 @@ pub fn get_cdc_text(data: &CDCRow<'_>, column: &str) -> String {
         .to_string()
 }
 
+pub fn get_cdc_uuid_list(data: &CDCRow<'_>, column: &str) -> Vec<Uuid> {
+    data.get_value(column)
+        .as_ref()
+        .and_then(|v| v.as_list())
+        .map(|list| list.iter().filter_map(|v| v.as_uuid()).collect())
+        .unwrap_or_default()
+}
+
+pub fn try_get_cdc_text(data: &CDCRow<'_>, column: &str) -> Option<String> {
+    data.get_value(column)
+        .as_ref()
+        .and_then(|v| v.as_text())
+        .map(|v| v.to_string())
+}
+
 pub fn get_cdc_date(data: &CDCRow<'_>, column: &str) -> String {
     let days = data
         .get_value(column)
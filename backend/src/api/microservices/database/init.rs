@@ -1,6 +1,10 @@
 use super::schema::{
     BOILER_SWAP_KEYSPACE,
-    columns::boiler_swap::{items, users},
+    columns::boiler_swap::{
+        catalog, favorites_by_item, favorites_by_user, item_photos, item_reports, items,
+        items_by_expiration, items_by_owner, messages, moderation_queue, passkeys,
+        passkeys_by_user, saved_searches, saved_searches_by_type, threads_by_user, users,
+    },
     tables,
 };
 use crate::{AppError, config::try_load};
@@ -14,13 +18,89 @@ use std::sync::Arc;
 pub struct BoilerSwap {
     pub get_user: PreparedStatement,
     pub insert_user: PreparedStatement,
+    pub get_totp: PreparedStatement,
+    pub update_totp: PreparedStatement,
+    pub remove_recovery_code: PreparedStatement,
+    pub insert_passkey: PreparedStatement,
+    pub insert_user_passkey: PreparedStatement,
+    pub get_passkey: PreparedStatement,
+    pub get_user_passkeys: PreparedStatement,
+    pub update_sign_count: PreparedStatement,
+    pub delete_passkey: PreparedStatement,
+    pub delete_user_passkey: PreparedStatement,
+    pub update_passkey_owner: PreparedStatement,
+    pub get_user_row: PreparedStatement,
+    pub insert_migrated_user: PreparedStatement,
+    pub delete_user: PreparedStatement,
+    pub update_item_owner: PreparedStatement,
     pub check_lock: PreparedStatement,
     pub update_lock: PreparedStatement,
     pub unlock_account: PreparedStatement,
     pub insert_item: PreparedStatement,
     pub get_items: PreparedStatement,
     pub delete_item: PreparedStatement,
-    pub get_cron_items: PreparedStatement,
+    pub insert_expiring_item: PreparedStatement,
+    pub get_expiring_item_ids: PreparedStatement,
+    pub get_expiration_dates: PreparedStatement,
+    pub get_admin_items: PreparedStatement,
+    pub get_all_expiration_dates: PreparedStatement,
+    pub peek_expiration_index: PreparedStatement,
+    pub delete_expiration_bucket: PreparedStatement,
+    pub get_reminder_items: PreparedStatement,
+    pub insert_owned_item: PreparedStatement,
+    pub get_owned_item_ids: PreparedStatement,
+    pub get_items_by_ids: PreparedStatement,
+    pub delete_owned_item: PreparedStatement,
+    pub get_item_owner: PreparedStatement,
+    pub get_item_ttl: PreparedStatement,
+    pub update_item: PreparedStatement,
+    pub get_renewal_item: PreparedStatement,
+    pub renew_item: PreparedStatement,
+    pub get_item_photo_ids: PreparedStatement,
+    pub add_item_photo: PreparedStatement,
+    pub insert_item_photo: PreparedStatement,
+    pub get_item_photo: PreparedStatement,
+    pub delete_item_photos: PreparedStatement,
+    pub update_item_status: PreparedStatement,
+    pub insert_user_thread: PreparedStatement,
+    pub get_user_threads: PreparedStatement,
+    pub get_user_thread: PreparedStatement,
+    pub get_user_thread_rows: PreparedStatement,
+    pub delete_user_thread: PreparedStatement,
+    pub insert_message: PreparedStatement,
+    pub get_messages: PreparedStatement,
+    pub get_thread_messages: PreparedStatement,
+    pub insert_migrated_message: PreparedStatement,
+    pub delete_thread_messages: PreparedStatement,
+    pub insert_saved_search: PreparedStatement,
+    pub get_saved_searches: PreparedStatement,
+    pub get_all_saved_searches: PreparedStatement,
+    pub get_saved_search_type: PreparedStatement,
+    pub delete_saved_search: PreparedStatement,
+    pub insert_alert_search: PreparedStatement,
+    pub get_alert_searches: PreparedStatement,
+    pub peek_alert_searches: PreparedStatement,
+    pub delete_alert_search: PreparedStatement,
+    pub insert_report: PreparedStatement,
+    pub count_reports: PreparedStatement,
+    pub hide_item: PreparedStatement,
+    pub insert_moderation_item: PreparedStatement,
+    pub get_moderation_queue: PreparedStatement,
+    pub delete_reports: PreparedStatement,
+    pub delete_moderation_item: PreparedStatement,
+    pub get_user_role: PreparedStatement,
+    pub update_role: PreparedStatement,
+    pub get_catalog: PreparedStatement,
+    pub insert_catalog_entry: PreparedStatement,
+    pub claim_catalog_entry: PreparedStatement,
+    pub insert_favorite: PreparedStatement,
+    pub insert_item_favorite: PreparedStatement,
+    pub get_favorite_ids: PreparedStatement,
+    pub delete_favorite: PreparedStatement,
+    pub delete_item_favorite: PreparedStatement,
+    pub get_item_favorites: PreparedStatement,
+    pub get_user_favorite: PreparedStatement,
+    pub delete_item_favorites: PreparedStatement,
 }
 
 #[derive(Clone)]
@@ -33,9 +113,10 @@ impl BoilerSwap {
         Ok(Self {
             get_user: session
             .prepare(format!(
-                "SELECT {}, {} FROM {}.{} WHERE {} = ?",
+                "SELECT {}, {}, {} FROM {}.{} WHERE {} = ?",
                 users::PASSWORD_HASH,
                 users::LOCKED,
+                users::ROLE,
                 BOILER_SWAP_KEYSPACE,
                 tables::boiler_swap::USERS,
                 users::PRIMARY_KEY
@@ -52,10 +133,167 @@ impl BoilerSwap {
                 users::TTL
             ))
             .await?,
+        get_totp: session
+            .prepare(format!(
+                "SELECT {}, {} FROM {}.{} WHERE {} = ?",
+                users::TOTP_SECRET,
+                users::RECOVERY_CODES,
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::USERS,
+                users::PRIMARY_KEY
+            ))
+            .await?,
+        update_totp: session
+            .prepare(format!(
+                "UPDATE {}.{} USING TTL {} SET {} = ?, {} = ? WHERE {} = ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::USERS,
+                users::TTL,
+                users::TOTP_SECRET,
+                users::RECOVERY_CODES,
+                users::PRIMARY_KEY
+            ))
+            .await?,
+        remove_recovery_code: session
+            .prepare(format!(
+                "UPDATE {}.{} SET {} = {} - ? WHERE {} = ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::USERS,
+                users::RECOVERY_CODES,
+                users::RECOVERY_CODES,
+                users::PRIMARY_KEY
+            ))
+            .await?,
+        insert_passkey: session
+            .prepare(format!(
+                "INSERT INTO {}.{} ({}, {}, {}, {}) VALUES (?, ?, ?, ?) USING TTL {}",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::PASSKEYS,
+                passkeys::CREDENTIAL_ID,
+                passkeys::EMAIL,
+                passkeys::PUBLIC_KEY,
+                passkeys::SIGN_COUNT,
+                users::TTL
+            ))
+            .await?,
+        insert_user_passkey: session
+            .prepare(format!(
+                "INSERT INTO {}.{} ({}, {}, {}) VALUES (?, ?, ?) USING TTL {}",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::PASSKEYS_BY_USER,
+                passkeys_by_user::EMAIL,
+                passkeys_by_user::CREDENTIAL_ID,
+                passkeys_by_user::CREATED_AT,
+                users::TTL
+            ))
+            .await?,
+        get_passkey: session
+            .prepare(format!(
+                "SELECT {}, {}, {} FROM {}.{} WHERE {} = ?",
+                passkeys::EMAIL,
+                passkeys::PUBLIC_KEY,
+                passkeys::SIGN_COUNT,
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::PASSKEYS,
+                passkeys::PRIMARY_KEY
+            ))
+            .await?,
+        get_user_passkeys: session
+            .prepare(format!(
+                "SELECT {}, {} FROM {}.{} WHERE {} = ?",
+                passkeys_by_user::CREDENTIAL_ID,
+                passkeys_by_user::CREATED_AT,
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::PASSKEYS_BY_USER,
+                passkeys_by_user::PARTITION_KEY
+            ))
+            .await?,
+        update_sign_count: session
+            .prepare(format!(
+                "UPDATE {}.{} USING TTL {} SET {} = ? WHERE {} = ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::PASSKEYS,
+                users::TTL,
+                passkeys::SIGN_COUNT,
+                passkeys::PRIMARY_KEY
+            ))
+            .await?,
+        delete_passkey: session
+            .prepare(format!(
+                "DELETE FROM {}.{} WHERE {} = ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::PASSKEYS,
+                passkeys::PRIMARY_KEY
+            ))
+            .await?,
+        update_passkey_owner: session
+            .prepare(format!(
+                "UPDATE {}.{} USING TTL {} SET {} = ? WHERE {} = ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::PASSKEYS,
+                users::TTL,
+                passkeys::EMAIL,
+                passkeys::PRIMARY_KEY
+            ))
+            .await?,
+        get_user_row: session
+            .prepare(format!(
+                "SELECT {}, {}, {}, {}, {} FROM {}.{} WHERE {} = ?",
+                users::PASSWORD_HASH,
+                users::LOCKED,
+                users::ROLE,
+                users::TOTP_SECRET,
+                users::RECOVERY_CODES,
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::USERS,
+                users::PRIMARY_KEY
+            ))
+            .await?,
+        insert_migrated_user: session
+            .prepare(format!(
+                "INSERT INTO {}.{} ({}, {}, {}, {}, {}, {}) VALUES (?, ?, ?, ?, ?, ?) IF NOT EXISTS USING TTL {}",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::USERS,
+                users::EMAIL,
+                users::PASSWORD_HASH,
+                users::LOCKED,
+                users::ROLE,
+                users::TOTP_SECRET,
+                users::RECOVERY_CODES,
+                users::TTL
+            ))
+            .await?,
+        delete_user: session
+            .prepare(format!(
+                "DELETE FROM {}.{} WHERE {} = ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::USERS,
+                users::PRIMARY_KEY
+            ))
+            .await?,
+        update_item_owner: session
+            .prepare(format!(
+                "UPDATE {}.{} USING TTL ? SET {} = ? WHERE {} = ? IF EXISTS",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEMS,
+                items::OWNER,
+                items::PRIMARY_KEY
+            ))
+            .await?,
+        delete_user_passkey: session
+            .prepare(format!(
+                "DELETE FROM {}.{} WHERE {} = ? AND {} = ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::PASSKEYS_BY_USER,
+                passkeys_by_user::PARTITION_KEY,
+                passkeys_by_user::CLUSTERING_KEY
+            ))
+            .await?,
         check_lock: session
             .prepare(format!(
-                "SELECT {} FROM {}.{} WHERE {} = ?",
+                "SELECT {}, {} FROM {}.{} WHERE {} = ?",
                 users::LOCKED,
+                users::ROLE,
                 BOILER_SWAP_KEYSPACE,
                 tables::boiler_swap::USERS,
                 users::PRIMARY_KEY
@@ -82,7 +320,7 @@ impl BoilerSwap {
             .await?,
         insert_item: session
             .prepare(format!(
-                "INSERT INTO {}.{} ({}, {}, {}, {}, {}, {}, {}, {}) VALUES (?, ?, ?, ?, ?, ?, ?, ?) USING TTL ?",
+                "INSERT INTO {}.{} ({}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) USING TTL ?",
                 BOILER_SWAP_KEYSPACE,
                 tables::boiler_swap::ITEMS,
                 items::ITEM_ID,
@@ -93,12 +331,15 @@ impl BoilerSwap {
                 items::DESCRIPTION,
                 items::EMOJI,
                 items::EXPIRATION_DATE,
+                items::OWNER,
+                items::STATUS,
+                items::CAMPUS,
             ))
             .await?,
         get_items: session
             .prepare(
                 Statement::new(format!(
-                    "SELECT {}, {}, {}, {}, {}, {}, {}, {} FROM {}.{}", 
+                    "SELECT {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {} FROM {}.{}", 
                     items::ITEM_ID,
                     items::ITEM_TYPE,
                     items::TITLE,
@@ -107,22 +348,100 @@ impl BoilerSwap {
                     items::DESCRIPTION,
                     items::EMOJI,
                     items::EXPIRATION_DATE,
+                    items::PHOTOS,
+                    items::STATUS,
+                    items::HIDDEN,
+                    items::CAMPUS,
                     BOILER_SWAP_KEYSPACE,
                     tables::boiler_swap::ITEMS
                 )).with_page_size(100),
             )
             .await?,
-        get_cron_items: session
+        insert_expiring_item: session
+            .prepare(format!(
+                "INSERT INTO {}.{} ({}, {}) VALUES (?, ?) USING TTL ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEMS_BY_EXPIRATION,
+                items_by_expiration::EXPIRATION_DATE,
+                items_by_expiration::ITEM_ID,
+            ))
+            .await?,
+        get_expiring_item_ids: session
+            .prepare(
+                Statement::new(format!(
+                    "SELECT {} FROM {}.{} WHERE {} = ?",
+                    items_by_expiration::ITEM_ID,
+                    BOILER_SWAP_KEYSPACE,
+                    tables::boiler_swap::ITEMS_BY_EXPIRATION,
+                    items_by_expiration::PARTITION_KEY,
+                )).with_page_size(100),
+            )
+            .await?,
+        get_expiration_dates: session
+            .prepare(format!(
+                "SELECT {}, {} FROM {}.{} WHERE {} IN ?",
+                items::ITEM_ID,
+                items::EXPIRATION_DATE,
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEMS,
+                items::PRIMARY_KEY,
+            ))
+            .await?,
+        get_admin_items: session
+            .prepare(
+                Statement::new(format!(
+                    "SELECT {}, {}, {} FROM {}.{}",
+                    items::ITEM_ID,
+                    items::OWNER,
+                    items::HIDDEN,
+                    BOILER_SWAP_KEYSPACE,
+                    tables::boiler_swap::ITEMS,
+                ))
+                .with_page_size(50),
+            )
+            .await?,
+        get_all_expiration_dates: session
             .prepare(
                 Statement::new(format!(
-                    "SELECT {}, {} FROM {}.{}", 
+                    "SELECT {}, {} FROM {}.{}",
                     items::ITEM_ID,
                     items::EXPIRATION_DATE,
                     BOILER_SWAP_KEYSPACE,
-                    tables::boiler_swap::ITEMS
-                )).with_page_size(100),
+                    tables::boiler_swap::ITEMS,
+                ))
+                .with_page_size(100),
             )
             .await?,
+        peek_expiration_index: session
+            .prepare(format!(
+                "SELECT {} FROM {}.{} LIMIT 1",
+                items_by_expiration::ITEM_ID,
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEMS_BY_EXPIRATION,
+            ))
+            .await?,
+        delete_expiration_bucket: session
+            .prepare(format!(
+                "DELETE FROM {}.{} WHERE {} = ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEMS_BY_EXPIRATION,
+                items_by_expiration::PARTITION_KEY,
+            ))
+            .await?,
+        get_reminder_items: session
+            .prepare(format!(
+                "SELECT {}, {}, {}, {}, {}, {} FROM {}.{} WHERE {} IN ?",
+                items::ITEM_ID,
+                items::TITLE,
+                items::EXPIRATION_DATE,
+                items::OWNER,
+                items::STATUS,
+                items::HIDDEN,
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEMS,
+                items::PRIMARY_KEY,
+            ))
+            .await?,
         delete_item: session
             .prepare(format!(
                 "DELETE FROM {}.{} WHERE {} = ?",
@@ -131,96 +450,1201 @@ impl BoilerSwap {
                 items::ITEM_ID,
             ))
             .await?,
-        })
-    }
-}
-
-impl DatabaseQueries {
-    pub async fn init(session: &Session) -> Result<Self, AppError> {
-        Ok(Self {
-            boiler_swap: BoilerSwap::init(session).await?,
-        })
-    }
-}
-
-pub async fn init_database() -> Result<(Arc<Session>, DatabaseQueries), AppError> {
-    let database_uri = try_load::<String>("RUST_DB_URI", "scylladb:9042").unwrap();
-
-    let database_session: Session = SessionBuilder::new()
-        .known_node(database_uri)
-        .build()
-        .await?;
-
-    create_tables(&database_session).await?;
-
-    let database_queries = DatabaseQueries::init(&database_session).await?;
-
-    Ok((Arc::new(database_session), database_queries))
-}
-
-async fn create_tables(session: &Session) -> Result<(), AppError> {
-    session.query_unpaged(
-        format!("CREATE KEYSPACE IF NOT EXISTS {} WITH REPLICATION = {{'class': 'SimpleStrategy', 'replication_factor': 1}}", BOILER_SWAP_KEYSPACE),
-        &[],
-    )
-    .await?;
-
-    session
-        .query_unpaged(
-            format!(
-                "CREATE TABLE IF NOT EXISTS {}.{} (
-            {} {},
-            {} {},
-            {} {},
-            PRIMARY KEY({})
-        )",
+        insert_owned_item: session
+            .prepare(format!(
+                "INSERT INTO {}.{} ({}, {}) VALUES (?, ?) USING TTL ?",
                 BOILER_SWAP_KEYSPACE,
-                tables::boiler_swap::USERS,
-                users::EMAIL,
-                users::EMAIL_TYPE,
-                users::PASSWORD_HASH,
-                users::PASSWORD_HASH_TYPE,
-                users::LOCKED,
-                users::LOCKED_TYPE,
-                users::PRIMARY_KEY,
-            ),
-            &[],
-        )
-        .await?;
-
-    session
-        .query_unpaged(
-            format!(
-                "CREATE TABLE IF NOT EXISTS {}.{} (
-            {} {},
-            {} {},
-            {} {},
-            {} {},
-            {} {},
-            {} {},
-            {} {},
-            {} {},
-            PRIMARY KEY({})
-        ) WITH cdc = {{'enabled': true}}",
+                tables::boiler_swap::ITEMS_BY_OWNER,
+                items_by_owner::OWNER,
+                items_by_owner::ITEM_ID,
+            ))
+            .await?,
+        get_owned_item_ids: session
+            .prepare(format!(
+                "SELECT {} FROM {}.{} WHERE {} = ?",
+                items_by_owner::ITEM_ID,
                 BOILER_SWAP_KEYSPACE,
-                tables::boiler_swap::ITEMS,
+                tables::boiler_swap::ITEMS_BY_OWNER,
+                items_by_owner::PARTITION_KEY,
+            ))
+            .await?,
+        get_items_by_ids: session
+            .prepare(format!(
+                "SELECT {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {} FROM {}.{} WHERE {} IN ?",
                 items::ITEM_ID,
-                items::ITEM_ID_TYPE,
                 items::ITEM_TYPE,
-                items::ITEM_TYPE_TYPE,
                 items::TITLE,
-                items::TITLE_TYPE,
                 items::CONDITION,
-                items::CONDITION_TYPE,
                 items::LOCATION,
-                items::LOCATION_TYPE,
                 items::DESCRIPTION,
-                items::DESCRIPTION_TYPE,
                 items::EMOJI,
-                items::EMOJI_TYPE,
                 items::EXPIRATION_DATE,
-                items::EXPIRATION_DATE_TYPE,
+                items::PHOTOS,
+                items::STATUS,
+                items::HIDDEN,
+                items::CAMPUS,
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEMS,
+                items::PRIMARY_KEY,
+            ))
+            .await?,
+        delete_owned_item: session
+            .prepare(format!(
+                "DELETE FROM {}.{} WHERE {} = ? AND {} = ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEMS_BY_OWNER,
+                items_by_owner::PARTITION_KEY,
+                items_by_owner::CLUSTERING_KEY,
+            ))
+            .await?,
+        get_item_owner: session
+            .prepare(format!(
+                "SELECT {} FROM {}.{} WHERE {} = ?",
+                items::OWNER,
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEMS,
+                items::PRIMARY_KEY,
+            ))
+            .await?,
+        get_item_ttl: session
+            .prepare(format!(
+                "SELECT TTL({}) FROM {}.{} WHERE {} = ?",
+                items::EXPIRATION_DATE,
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEMS,
+                items::PRIMARY_KEY,
+            ))
+            .await?,
+        update_item: session
+            .prepare(format!(
+                "UPDATE {}.{} USING TTL ? SET {} = ?, {} = ?, {} = ?, {} = ?, {} = ?, {} = ? WHERE {} = ? IF EXISTS",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEMS,
+                items::ITEM_TYPE,
+                items::TITLE,
+                items::CONDITION,
+                items::LOCATION,
+                items::DESCRIPTION,
+                items::EMOJI,
                 items::PRIMARY_KEY,
+            ))
+            .await?,
+        get_renewal_item: session
+            .prepare(format!(
+                "SELECT {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {} FROM {}.{} WHERE {} = ?",
+                items::ITEM_TYPE,
+                items::TITLE,
+                items::CONDITION,
+                items::LOCATION,
+                items::DESCRIPTION,
+                items::EMOJI,
+                items::EXPIRATION_DATE,
+                items::OWNER,
+                items::PHOTOS,
+                items::STATUS,
+                items::HIDDEN,
+                items::RENEWALS,
+                items::CAMPUS,
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEMS,
+                items::PRIMARY_KEY,
+            ))
+            .await?,
+        renew_item: session
+            .prepare(format!(
+                "UPDATE {}.{} USING TTL ? SET {} = ?, {} = ?, {} = ?, {} = ?, {} = ?, {} = ?, {} = ?, {} = ?, {} = ?, {} = ?, {} = ?, {} = ?, {} = ? WHERE {} = ? IF {} = ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEMS,
+                items::ITEM_TYPE,
+                items::TITLE,
+                items::CONDITION,
+                items::LOCATION,
+                items::DESCRIPTION,
+                items::EMOJI,
+                items::EXPIRATION_DATE,
+                items::OWNER,
+                items::PHOTOS,
+                items::STATUS,
+                items::HIDDEN,
+                items::RENEWALS,
+                items::CAMPUS,
+                items::PRIMARY_KEY,
+                items::EXPIRATION_DATE,
+            ))
+            .await?,
+        get_item_photo_ids: session
+            .prepare(format!(
+                "SELECT {} FROM {}.{} WHERE {} = ?",
+                items::PHOTOS,
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEMS,
+                items::PRIMARY_KEY,
+            ))
+            .await?,
+        add_item_photo: session
+            .prepare(format!(
+                "UPDATE {}.{} USING TTL ? SET {} = {} + ? WHERE {} = ? IF EXISTS",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEMS,
+                items::PHOTOS,
+                items::PHOTOS,
+                items::PRIMARY_KEY,
+            ))
+            .await?,
+        insert_item_photo: session
+            .prepare(format!(
+                "INSERT INTO {}.{} ({}, {}, {}) VALUES (?, ?, ?) USING TTL ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEM_PHOTOS,
+                item_photos::ITEM_ID,
+                item_photos::PHOTO_ID,
+                item_photos::PHOTO,
+            ))
+            .await?,
+        get_item_photo: session
+            .prepare(format!(
+                "SELECT {} FROM {}.{} WHERE {} = ? AND {} = ?",
+                item_photos::PHOTO,
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEM_PHOTOS,
+                item_photos::PARTITION_KEY,
+                item_photos::CLUSTERING_KEY,
+            ))
+            .await?,
+        delete_item_photos: session
+            .prepare(format!(
+                "DELETE FROM {}.{} WHERE {} = ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEM_PHOTOS,
+                item_photos::PARTITION_KEY,
+            ))
+            .await?,
+        update_item_status: session
+            .prepare(format!(
+                "UPDATE {}.{} USING TTL ? SET {} = ? WHERE {} = ? IF EXISTS",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEMS,
+                items::STATUS,
+                items::PRIMARY_KEY,
+            ))
+            .await?,
+        insert_user_thread: session
+            .prepare(format!(
+                "INSERT INTO {}.{} ({}, {}, {}, {}, {}) VALUES (?, ?, ?, ?, ?) USING TTL {}",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::THREADS_BY_USER,
+                threads_by_user::EMAIL,
+                threads_by_user::THREAD_ID,
+                threads_by_user::ITEM_ID,
+                threads_by_user::OTHER_EMAIL,
+                threads_by_user::ROLE,
+                threads_by_user::TTL,
+            ))
+            .await?,
+        get_user_threads: session
+            .prepare(format!(
+                "SELECT {}, {}, {} FROM {}.{} WHERE {} = ?",
+                threads_by_user::THREAD_ID,
+                threads_by_user::ITEM_ID,
+                threads_by_user::ROLE,
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::THREADS_BY_USER,
+                threads_by_user::PARTITION_KEY,
+            ))
+            .await?,
+        get_user_thread: session
+            .prepare(format!(
+                "SELECT {} FROM {}.{} WHERE {} = ? AND {} = ?",
+                threads_by_user::OTHER_EMAIL,
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::THREADS_BY_USER,
+                threads_by_user::PARTITION_KEY,
+                threads_by_user::CLUSTERING_KEY,
+            ))
+            .await?,
+        get_user_thread_rows: session
+            .prepare(format!(
+                "SELECT {}, {}, {}, {} FROM {}.{} WHERE {} = ?",
+                threads_by_user::THREAD_ID,
+                threads_by_user::ITEM_ID,
+                threads_by_user::OTHER_EMAIL,
+                threads_by_user::ROLE,
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::THREADS_BY_USER,
+                threads_by_user::PARTITION_KEY,
+            ))
+            .await?,
+        delete_user_thread: session
+            .prepare(format!(
+                "DELETE FROM {}.{} WHERE {} = ? AND {} = ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::THREADS_BY_USER,
+                threads_by_user::PARTITION_KEY,
+                threads_by_user::CLUSTERING_KEY,
+            ))
+            .await?,
+        insert_message: session
+            .prepare(format!(
+                "INSERT INTO {}.{} ({}, {}, {}, {}, {}) VALUES (?, ?, ?, ?, ?) USING TTL {}",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::MESSAGES,
+                messages::THREAD_ID,
+                messages::SENT_AT,
+                messages::MESSAGE_ID,
+                messages::SENDER,
+                messages::BODY,
+                messages::TTL,
+            ))
+            .await?,
+        get_messages: session
+            .prepare(
+                Statement::new(format!(
+                    "SELECT {}, {}, {}, {} FROM {}.{} WHERE {} = ?",
+                    messages::SENT_AT,
+                    messages::MESSAGE_ID,
+                    messages::SENDER,
+                    messages::BODY,
+                    BOILER_SWAP_KEYSPACE,
+                    tables::boiler_swap::MESSAGES,
+                    messages::PARTITION_KEY,
+                )).with_page_size(100),
+            )
+            .await?,
+        get_thread_messages: session
+            .prepare(
+                Statement::new(format!(
+                    "SELECT {}, {}, {}, {}, TTL({}) FROM {}.{} WHERE {} = ?",
+                    messages::SENT_AT,
+                    messages::MESSAGE_ID,
+                    messages::SENDER,
+                    messages::BODY,
+                    messages::BODY,
+                    BOILER_SWAP_KEYSPACE,
+                    tables::boiler_swap::MESSAGES,
+                    messages::PARTITION_KEY,
+                )).with_page_size(100),
+            )
+            .await?,
+        insert_migrated_message: session
+            .prepare(format!(
+                "INSERT INTO {}.{} ({}, {}, {}, {}, {}) VALUES (?, ?, ?, ?, ?) USING TTL ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::MESSAGES,
+                messages::THREAD_ID,
+                messages::SENT_AT,
+                messages::MESSAGE_ID,
+                messages::SENDER,
+                messages::BODY,
+            ))
+            .await?,
+        delete_thread_messages: session
+            .prepare(format!(
+                "DELETE FROM {}.{} WHERE {} = ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::MESSAGES,
+                messages::PARTITION_KEY,
+            ))
+            .await?,
+        insert_saved_search: session
+            .prepare(format!(
+                "INSERT INTO {}.{} ({}, {}, {}, {}, {}, {}) VALUES (?, ?, ?, ?, ?, ?) USING TTL {}",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::SAVED_SEARCHES,
+                saved_searches::EMAIL,
+                saved_searches::SEARCH_ID,
+                saved_searches::QUERY,
+                saved_searches::ITEM_TYPE,
+                saved_searches::CONDITION,
+                saved_searches::LOCATION,
+                saved_searches::TTL,
+            ))
+            .await?,
+        get_saved_searches: session
+            .prepare(format!(
+                "SELECT {}, {}, {}, {}, {}, {} FROM {}.{} WHERE {} = ?",
+                saved_searches::EMAIL,
+                saved_searches::SEARCH_ID,
+                saved_searches::QUERY,
+                saved_searches::ITEM_TYPE,
+                saved_searches::CONDITION,
+                saved_searches::LOCATION,
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::SAVED_SEARCHES,
+                saved_searches::PARTITION_KEY,
+            ))
+            .await?,
+        get_all_saved_searches: session
+            .prepare(
+                Statement::new(format!(
+                    "SELECT {}, {}, {}, {}, {}, {}, TTL({}) FROM {}.{}",
+                    saved_searches::EMAIL,
+                    saved_searches::SEARCH_ID,
+                    saved_searches::QUERY,
+                    saved_searches::ITEM_TYPE,
+                    saved_searches::CONDITION,
+                    saved_searches::LOCATION,
+                    saved_searches::QUERY,
+                    BOILER_SWAP_KEYSPACE,
+                    tables::boiler_swap::SAVED_SEARCHES,
+                )).with_page_size(100),
+            )
+            .await?,
+        get_saved_search_type: session
+            .prepare(format!(
+                "SELECT {} FROM {}.{} WHERE {} = ? AND {} = ?",
+                saved_searches::ITEM_TYPE,
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::SAVED_SEARCHES,
+                saved_searches::PARTITION_KEY,
+                saved_searches::CLUSTERING_KEY,
+            ))
+            .await?,
+        delete_saved_search: session
+            .prepare(format!(
+                "DELETE FROM {}.{} WHERE {} = ? AND {} = ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::SAVED_SEARCHES,
+                saved_searches::PARTITION_KEY,
+                saved_searches::CLUSTERING_KEY,
+            ))
+            .await?,
+        insert_alert_search: session
+            .prepare(format!(
+                "INSERT INTO {}.{} ({}, {}, {}, {}, {}, {}, {}) VALUES (?, ?, ?, ?, ?, ?, ?) USING TTL ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::SAVED_SEARCHES_BY_TYPE,
+                saved_searches_by_type::CAMPUS,
+                saved_searches_by_type::ITEM_TYPE,
+                saved_searches_by_type::EMAIL,
+                saved_searches_by_type::SEARCH_ID,
+                saved_searches_by_type::QUERY,
+                saved_searches_by_type::CONDITION,
+                saved_searches_by_type::LOCATION,
+            ))
+            .await?,
+        get_alert_searches: session
+            .prepare(
+                Statement::new(format!(
+                    "SELECT {}, {}, {}, {}, {}, {} FROM {}.{} WHERE {} = ? AND {} = ?",
+                    saved_searches_by_type::EMAIL,
+                    saved_searches_by_type::SEARCH_ID,
+                    saved_searches_by_type::QUERY,
+                    saved_searches_by_type::ITEM_TYPE,
+                    saved_searches_by_type::CONDITION,
+                    saved_searches_by_type::LOCATION,
+                    BOILER_SWAP_KEYSPACE,
+                    tables::boiler_swap::SAVED_SEARCHES_BY_TYPE,
+                    saved_searches_by_type::CAMPUS,
+                    saved_searches_by_type::ITEM_TYPE,
+                )).with_page_size(100),
+            )
+            .await?,
+        peek_alert_searches: session
+            .prepare(format!(
+                "SELECT {} FROM {}.{} LIMIT 1",
+                saved_searches_by_type::SEARCH_ID,
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::SAVED_SEARCHES_BY_TYPE,
+            ))
+            .await?,
+        delete_alert_search: session
+            .prepare(format!(
+                "DELETE FROM {}.{} WHERE {} = ? AND {} = ? AND {} = ? AND {} = ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::SAVED_SEARCHES_BY_TYPE,
+                saved_searches_by_type::CAMPUS,
+                saved_searches_by_type::ITEM_TYPE,
+                saved_searches_by_type::EMAIL,
+                saved_searches_by_type::SEARCH_ID,
+            ))
+            .await?,
+        insert_report: session
+            .prepare(format!(
+                "INSERT INTO {}.{} ({}, {}, {}) VALUES (?, ?, ?) USING TTL {}",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEM_REPORTS,
+                item_reports::ITEM_ID,
+                item_reports::REPORTER,
+                item_reports::REASON,
+                item_reports::TTL,
+            ))
+            .await?,
+        count_reports: session
+            .prepare(format!(
+                "SELECT COUNT(*) FROM {}.{} WHERE {} = ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEM_REPORTS,
+                item_reports::PARTITION_KEY,
+            ))
+            .await?,
+        hide_item: session
+            .prepare(format!(
+                "UPDATE {}.{} USING TTL ? SET {} = ? WHERE {} = ? IF EXISTS",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEMS,
+                items::HIDDEN,
+                items::PRIMARY_KEY,
+            ))
+            .await?,
+        insert_moderation_item: session
+            .prepare(format!(
+                "INSERT INTO {}.{} ({}, {}, {}) VALUES (?, ?, ?) USING TTL {}",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::MODERATION_QUEUE,
+                moderation_queue::ITEM_ID,
+                moderation_queue::REPORTS,
+                moderation_queue::QUEUED_AT,
+                moderation_queue::TTL,
+            ))
+            .await?,
+        get_moderation_queue: session
+            .prepare(
+                Statement::new(format!(
+                    "SELECT {}, {}, {} FROM {}.{}",
+                    moderation_queue::ITEM_ID,
+                    moderation_queue::REPORTS,
+                    moderation_queue::QUEUED_AT,
+                    BOILER_SWAP_KEYSPACE,
+                    tables::boiler_swap::MODERATION_QUEUE,
+                )).with_page_size(100),
+            )
+            .await?,
+        delete_reports: session
+            .prepare(format!(
+                "DELETE FROM {}.{} WHERE {} = ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEM_REPORTS,
+                item_reports::PARTITION_KEY,
+            ))
+            .await?,
+        delete_moderation_item: session
+            .prepare(format!(
+                "DELETE FROM {}.{} WHERE {} = ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::MODERATION_QUEUE,
+                moderation_queue::PRIMARY_KEY,
+            ))
+            .await?,
+        get_user_role: session
+            .prepare(format!(
+                "SELECT {} FROM {}.{} WHERE {} = ?",
+                users::ROLE,
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::USERS,
+                users::PRIMARY_KEY
+            ))
+            .await?,
+        update_role: session
+            .prepare(format!(
+                "UPDATE {}.{} SET {} = ? WHERE {} = ? IF EXISTS",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::USERS,
+                users::ROLE,
+                users::PRIMARY_KEY
+            ))
+            .await?,
+        get_catalog: session
+            .prepare(format!(
+                "SELECT {}, {}, {}, {}, {}, {}, {} FROM {}.{}",
+                catalog::KIND,
+                catalog::CODE,
+                catalog::NAME,
+                catalog::LABEL,
+                catalog::LATITUDE,
+                catalog::LONGITUDE,
+                catalog::CAMPUS,
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::CATALOG,
+            ))
+            .await?,
+        insert_catalog_entry: session
+            .prepare(format!(
+                "INSERT INTO {}.{} ({}, {}, {}, {}, {}, {}, {}) VALUES (?, ?, ?, ?, ?, ?, ?)",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::CATALOG,
+                catalog::KIND,
+                catalog::CODE,
+                catalog::NAME,
+                catalog::LABEL,
+                catalog::LATITUDE,
+                catalog::LONGITUDE,
+                catalog::CAMPUS,
+            ))
+            .await?,
+        claim_catalog_entry: session
+            .prepare(format!(
+                "INSERT INTO {}.{} ({}, {}, {}, {}, {}, {}, {}) VALUES (?, ?, ?, ?, ?, ?, ?) IF NOT EXISTS",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::CATALOG,
+                catalog::KIND,
+                catalog::CODE,
+                catalog::NAME,
+                catalog::LABEL,
+                catalog::LATITUDE,
+                catalog::LONGITUDE,
+                catalog::CAMPUS,
+            ))
+            .await?,
+        insert_favorite: session
+            .prepare(format!(
+                "INSERT INTO {}.{} ({}, {}) VALUES (?, ?) USING TTL {}",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::FAVORITES_BY_USER,
+                favorites_by_user::EMAIL,
+                favorites_by_user::ITEM_ID,
+                favorites_by_user::TTL,
+            ))
+            .await?,
+        insert_item_favorite: session
+            .prepare(format!(
+                "INSERT INTO {}.{} ({}, {}, {}, {}) VALUES (?, ?, ?, ?) USING TTL {}",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::FAVORITES_BY_ITEM,
+                favorites_by_item::ITEM_ID,
+                favorites_by_item::EMAIL,
+                favorites_by_item::TITLE,
+                favorites_by_item::NOTIFY,
+                favorites_by_item::TTL,
+            ))
+            .await?,
+        get_favorite_ids: session
+            .prepare(format!(
+                "SELECT {} FROM {}.{} WHERE {} = ?",
+                favorites_by_user::ITEM_ID,
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::FAVORITES_BY_USER,
+                favorites_by_user::PARTITION_KEY,
+            ))
+            .await?,
+        delete_favorite: session
+            .prepare(format!(
+                "DELETE FROM {}.{} WHERE {} = ? AND {} = ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::FAVORITES_BY_USER,
+                favorites_by_user::PARTITION_KEY,
+                favorites_by_user::CLUSTERING_KEY,
+            ))
+            .await?,
+        delete_item_favorite: session
+            .prepare(format!(
+                "DELETE FROM {}.{} WHERE {} = ? AND {} = ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::FAVORITES_BY_ITEM,
+                favorites_by_item::PARTITION_KEY,
+                favorites_by_item::CLUSTERING_KEY,
+            ))
+            .await?,
+        get_item_favorites: session
+            .prepare(format!(
+                "SELECT {}, {}, {} FROM {}.{} WHERE {} = ?",
+                favorites_by_item::EMAIL,
+                favorites_by_item::TITLE,
+                favorites_by_item::NOTIFY,
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::FAVORITES_BY_ITEM,
+                favorites_by_item::PARTITION_KEY,
+            ))
+            .await?,
+        get_user_favorite: session
+            .prepare(format!(
+                "SELECT {}, {} FROM {}.{} WHERE {} = ? AND {} = ?",
+                favorites_by_item::TITLE,
+                favorites_by_item::NOTIFY,
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::FAVORITES_BY_ITEM,
+                favorites_by_item::PARTITION_KEY,
+                favorites_by_item::CLUSTERING_KEY,
+            ))
+            .await?,
+        delete_item_favorites: session
+            .prepare(format!(
+                "DELETE FROM {}.{} WHERE {} = ?",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::FAVORITES_BY_ITEM,
+                favorites_by_item::PARTITION_KEY,
+            ))
+            .await?,
+        })
+    }
+}
+
+impl DatabaseQueries {
+    pub async fn init(session: &Session) -> Result<Self, AppError> {
+        Ok(Self {
+            boiler_swap: BoilerSwap::init(session).await?,
+        })
+    }
+}
+
+pub async fn init_database() -> Result<(Arc<Session>, DatabaseQueries), AppError> {
+    let database_uri = try_load::<String>("RUST_DB_URI", "scylladb:9042").unwrap();
+
+    let database_session: Session = SessionBuilder::new()
+        .known_node(database_uri)
+        .build()
+        .await?;
+
+    create_tables(&database_session).await?;
+
+    let database_queries = DatabaseQueries::init(&database_session).await?;
+
+    Ok((Arc::new(database_session), database_queries))
+}
+
+// CREATE TABLE IF NOT EXISTS leaves deployed tables alone, so later columns are added here
+async fn add_missing_columns(
+    session: &Session,
+    table: &str,
+    columns: &[(&str, &str)],
+) -> Result<(), AppError> {
+    let existing_columns: Vec<String> = session
+        .query_unpaged(
+            "SELECT column_name FROM system_schema.columns WHERE keyspace_name = ? AND table_name = ?",
+            (BOILER_SWAP_KEYSPACE, table),
+        )
+        .await?
+        .into_rows_result()?
+        .rows::<(String,)>()?
+        .map(|row| row.map(|(column_name,)| column_name))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (column, column_type) in columns {
+        if existing_columns.iter().any(|existing| existing == column) {
+            continue;
+        }
+
+        session
+            .query_unpaged(
+                format!(
+                    "ALTER TABLE {}.{} ADD {} {}",
+                    BOILER_SWAP_KEYSPACE, table, column, column_type
+                ),
+                &[],
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+async fn create_tables(session: &Session) -> Result<(), AppError> {
+    session.query_unpaged(
+        format!("CREATE KEYSPACE IF NOT EXISTS {} WITH REPLICATION = {{'class': 'SimpleStrategy', 'replication_factor': 1}}", BOILER_SWAP_KEYSPACE),
+        &[],
+    )
+    .await?;
+
+    session
+        .query_unpaged(
+            format!(
+                "CREATE TABLE IF NOT EXISTS {}.{} (
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            PRIMARY KEY({})
+        )",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::USERS,
+                users::EMAIL,
+                users::EMAIL_TYPE,
+                users::PASSWORD_HASH,
+                users::PASSWORD_HASH_TYPE,
+                users::LOCKED,
+                users::LOCKED_TYPE,
+                users::ROLE,
+                users::ROLE_TYPE,
+                users::TOTP_SECRET,
+                users::TOTP_SECRET_TYPE,
+                users::RECOVERY_CODES,
+                users::RECOVERY_CODES_TYPE,
+                users::PRIMARY_KEY,
+            ),
+            &[],
+        )
+        .await?;
+
+    add_missing_columns(
+        session,
+        tables::boiler_swap::USERS,
+        &[
+            (users::ROLE, users::ROLE_TYPE),
+            (users::TOTP_SECRET, users::TOTP_SECRET_TYPE),
+            (users::RECOVERY_CODES, users::RECOVERY_CODES_TYPE),
+        ],
+    )
+    .await?;
+
+    session
+        .query_unpaged(
+            format!(
+                "CREATE TABLE IF NOT EXISTS {}.{} (
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            PRIMARY KEY({})
+        )",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::PASSKEYS,
+                passkeys::CREDENTIAL_ID,
+                passkeys::CREDENTIAL_ID_TYPE,
+                passkeys::EMAIL,
+                passkeys::EMAIL_TYPE,
+                passkeys::PUBLIC_KEY,
+                passkeys::PUBLIC_KEY_TYPE,
+                passkeys::SIGN_COUNT,
+                passkeys::SIGN_COUNT_TYPE,
+                passkeys::PRIMARY_KEY,
+            ),
+            &[],
+        )
+        .await?;
+
+    session
+        .query_unpaged(
+            format!(
+                "CREATE TABLE IF NOT EXISTS {}.{} (
+            {} {},
+            {} {},
+            {} {},
+            PRIMARY KEY(({}), {})
+        )",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::PASSKEYS_BY_USER,
+                passkeys_by_user::EMAIL,
+                passkeys_by_user::EMAIL_TYPE,
+                passkeys_by_user::CREDENTIAL_ID,
+                passkeys_by_user::CREDENTIAL_ID_TYPE,
+                passkeys_by_user::CREATED_AT,
+                passkeys_by_user::CREATED_AT_TYPE,
+                passkeys_by_user::PARTITION_KEY,
+                passkeys_by_user::CLUSTERING_KEY,
+            ),
+            &[],
+        )
+        .await?;
+
+    session
+        .query_unpaged(
+            format!(
+                "CREATE TABLE IF NOT EXISTS {}.{} (
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            PRIMARY KEY({})
+        ) WITH cdc = {{'enabled': true, 'postimage': true}}",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEMS,
+                items::ITEM_ID,
+                items::ITEM_ID_TYPE,
+                items::ITEM_TYPE,
+                items::ITEM_TYPE_TYPE,
+                items::TITLE,
+                items::TITLE_TYPE,
+                items::CONDITION,
+                items::CONDITION_TYPE,
+                items::LOCATION,
+                items::LOCATION_TYPE,
+                items::DESCRIPTION,
+                items::DESCRIPTION_TYPE,
+                items::EMOJI,
+                items::EMOJI_TYPE,
+                items::EXPIRATION_DATE,
+                items::EXPIRATION_DATE_TYPE,
+                items::OWNER,
+                items::OWNER_TYPE,
+                items::PHOTOS,
+                items::PHOTOS_TYPE,
+                items::STATUS,
+                items::STATUS_TYPE,
+                items::HIDDEN,
+                items::HIDDEN_TYPE,
+                items::RENEWALS,
+                items::RENEWALS_TYPE,
+                items::CAMPUS,
+                items::CAMPUS_TYPE,
+                items::PRIMARY_KEY,
+            ),
+            &[],
+        )
+        .await?;
+
+    session
+        .query_unpaged(
+            format!(
+                "ALTER TABLE {}.{} WITH cdc = {{'enabled': true, 'postimage': true}}",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEMS,
+            ),
+            &[],
+        )
+        .await?;
+
+    add_missing_columns(
+        session,
+        tables::boiler_swap::ITEMS,
+        &[
+            (items::OWNER, items::OWNER_TYPE),
+            (items::PHOTOS, items::PHOTOS_TYPE),
+            (items::STATUS, items::STATUS_TYPE),
+            (items::HIDDEN, items::HIDDEN_TYPE),
+            (items::RENEWALS, items::RENEWALS_TYPE),
+            (items::CAMPUS, items::CAMPUS_TYPE),
+        ],
+    )
+    .await?;
+
+    session
+        .query_unpaged(
+            format!(
+                "CREATE TABLE IF NOT EXISTS {}.{} (
+            {} {},
+            {} {},
+            PRIMARY KEY(({}), {})
+        )",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEMS_BY_OWNER,
+                items_by_owner::OWNER,
+                items_by_owner::OWNER_TYPE,
+                items_by_owner::ITEM_ID,
+                items_by_owner::ITEM_ID_TYPE,
+                items_by_owner::PARTITION_KEY,
+                items_by_owner::CLUSTERING_KEY,
+            ),
+            &[],
+        )
+        .await?;
+
+    session
+        .query_unpaged(
+            format!(
+                "CREATE TABLE IF NOT EXISTS {}.{} (
+            {} {},
+            {} {},
+            PRIMARY KEY(({}), {})
+        )",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEMS_BY_EXPIRATION,
+                items_by_expiration::EXPIRATION_DATE,
+                items_by_expiration::EXPIRATION_DATE_TYPE,
+                items_by_expiration::ITEM_ID,
+                items_by_expiration::ITEM_ID_TYPE,
+                items_by_expiration::PARTITION_KEY,
+                items_by_expiration::CLUSTERING_KEY,
+            ),
+            &[],
+        )
+        .await?;
+
+    session
+        .query_unpaged(
+            format!(
+                "CREATE TABLE IF NOT EXISTS {}.{} (
+            {} {},
+            {} {},
+            {} {},
+            PRIMARY KEY(({}), {})
+        )",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEM_PHOTOS,
+                item_photos::ITEM_ID,
+                item_photos::ITEM_ID_TYPE,
+                item_photos::PHOTO_ID,
+                item_photos::PHOTO_ID_TYPE,
+                item_photos::PHOTO,
+                item_photos::PHOTO_TYPE,
+                item_photos::PARTITION_KEY,
+                item_photos::CLUSTERING_KEY,
+            ),
+            &[],
+        )
+        .await?;
+
+    session
+        .query_unpaged(
+            format!(
+                "CREATE TABLE IF NOT EXISTS {}.{} (
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            PRIMARY KEY(({}), {})
+        )",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::THREADS_BY_USER,
+                threads_by_user::EMAIL,
+                threads_by_user::EMAIL_TYPE,
+                threads_by_user::THREAD_ID,
+                threads_by_user::THREAD_ID_TYPE,
+                threads_by_user::ITEM_ID,
+                threads_by_user::ITEM_ID_TYPE,
+                threads_by_user::OTHER_EMAIL,
+                threads_by_user::OTHER_EMAIL_TYPE,
+                threads_by_user::ROLE,
+                threads_by_user::ROLE_TYPE,
+                threads_by_user::PARTITION_KEY,
+                threads_by_user::CLUSTERING_KEY,
+            ),
+            &[],
+        )
+        .await?;
+
+    session
+        .query_unpaged(
+            format!(
+                "CREATE TABLE IF NOT EXISTS {}.{} (
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            PRIMARY KEY(({}), {}, {})
+        ) WITH CLUSTERING ORDER BY ({} DESC, {} DESC)",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::MESSAGES,
+                messages::THREAD_ID,
+                messages::THREAD_ID_TYPE,
+                messages::SENT_AT,
+                messages::SENT_AT_TYPE,
+                messages::MESSAGE_ID,
+                messages::MESSAGE_ID_TYPE,
+                messages::SENDER,
+                messages::SENDER_TYPE,
+                messages::BODY,
+                messages::BODY_TYPE,
+                messages::PARTITION_KEY,
+                messages::SENT_AT,
+                messages::MESSAGE_ID,
+                messages::SENT_AT,
+                messages::MESSAGE_ID,
+            ),
+            &[],
+        )
+        .await?;
+
+    session
+        .query_unpaged(
+            format!(
+                "CREATE TABLE IF NOT EXISTS {}.{} (
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            PRIMARY KEY(({}), {})
+        )",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::SAVED_SEARCHES,
+                saved_searches::EMAIL,
+                saved_searches::EMAIL_TYPE,
+                saved_searches::SEARCH_ID,
+                saved_searches::SEARCH_ID_TYPE,
+                saved_searches::QUERY,
+                saved_searches::QUERY_TYPE,
+                saved_searches::ITEM_TYPE,
+                saved_searches::ITEM_TYPE_TYPE,
+                saved_searches::CONDITION,
+                saved_searches::CONDITION_TYPE,
+                saved_searches::LOCATION,
+                saved_searches::LOCATION_TYPE,
+                saved_searches::PARTITION_KEY,
+                saved_searches::CLUSTERING_KEY,
+            ),
+            &[],
+        )
+        .await?;
+
+    session
+        .query_unpaged(
+            format!(
+                "CREATE TABLE IF NOT EXISTS {}.{} (
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            PRIMARY KEY(({}, {}), {}, {})
+        )",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::SAVED_SEARCHES_BY_TYPE,
+                saved_searches_by_type::CAMPUS,
+                saved_searches_by_type::CAMPUS_TYPE,
+                saved_searches_by_type::ITEM_TYPE,
+                saved_searches_by_type::ITEM_TYPE_TYPE,
+                saved_searches_by_type::EMAIL,
+                saved_searches_by_type::EMAIL_TYPE,
+                saved_searches_by_type::SEARCH_ID,
+                saved_searches_by_type::SEARCH_ID_TYPE,
+                saved_searches_by_type::QUERY,
+                saved_searches_by_type::QUERY_TYPE,
+                saved_searches_by_type::CONDITION,
+                saved_searches_by_type::CONDITION_TYPE,
+                saved_searches_by_type::LOCATION,
+                saved_searches_by_type::LOCATION_TYPE,
+                saved_searches_by_type::CAMPUS,
+                saved_searches_by_type::ITEM_TYPE,
+                saved_searches_by_type::EMAIL,
+                saved_searches_by_type::SEARCH_ID,
+            ),
+            &[],
+        )
+        .await?;
+
+    session
+        .query_unpaged(
+            format!(
+                "CREATE TABLE IF NOT EXISTS {}.{} (
+            {} {},
+            {} {},
+            {} {},
+            PRIMARY KEY(({}), {})
+        )",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::ITEM_REPORTS,
+                item_reports::ITEM_ID,
+                item_reports::ITEM_ID_TYPE,
+                item_reports::REPORTER,
+                item_reports::REPORTER_TYPE,
+                item_reports::REASON,
+                item_reports::REASON_TYPE,
+                item_reports::PARTITION_KEY,
+                item_reports::CLUSTERING_KEY,
+            ),
+            &[],
+        )
+        .await?;
+
+    session
+        .query_unpaged(
+            format!(
+                "CREATE TABLE IF NOT EXISTS {}.{} (
+            {} {},
+            {} {},
+            {} {},
+            PRIMARY KEY({})
+        )",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::MODERATION_QUEUE,
+                moderation_queue::ITEM_ID,
+                moderation_queue::ITEM_ID_TYPE,
+                moderation_queue::REPORTS,
+                moderation_queue::REPORTS_TYPE,
+                moderation_queue::QUEUED_AT,
+                moderation_queue::QUEUED_AT_TYPE,
+                moderation_queue::PRIMARY_KEY,
+            ),
+            &[],
+        )
+        .await?;
+
+    session
+        .query_unpaged(
+            format!(
+                "CREATE TABLE IF NOT EXISTS {}.{} (
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            PRIMARY KEY(({}), {})
+        )",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::CATALOG,
+                catalog::KIND,
+                catalog::KIND_TYPE,
+                catalog::CODE,
+                catalog::CODE_TYPE,
+                catalog::NAME,
+                catalog::NAME_TYPE,
+                catalog::LABEL,
+                catalog::LABEL_TYPE,
+                catalog::LATITUDE,
+                catalog::LATITUDE_TYPE,
+                catalog::LONGITUDE,
+                catalog::LONGITUDE_TYPE,
+                catalog::CAMPUS,
+                catalog::CAMPUS_TYPE,
+                catalog::PARTITION_KEY,
+                catalog::CLUSTERING_KEY,
+            ),
+            &[],
+        )
+        .await?;
+
+    add_missing_columns(
+        session,
+        tables::boiler_swap::CATALOG,
+        &[
+            (catalog::LATITUDE, catalog::LATITUDE_TYPE),
+            (catalog::LONGITUDE, catalog::LONGITUDE_TYPE),
+            (catalog::CAMPUS, catalog::CAMPUS_TYPE),
+        ],
+    )
+    .await?;
+
+    session
+        .query_unpaged(
+            format!(
+                "CREATE TABLE IF NOT EXISTS {}.{} (
+            {} {},
+            {} {},
+            PRIMARY KEY(({}), {})
+        )",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::FAVORITES_BY_USER,
+                favorites_by_user::EMAIL,
+                favorites_by_user::EMAIL_TYPE,
+                favorites_by_user::ITEM_ID,
+                favorites_by_user::ITEM_ID_TYPE,
+                favorites_by_user::PARTITION_KEY,
+                favorites_by_user::CLUSTERING_KEY,
+            ),
+            &[],
+        )
+        .await?;
+
+    session
+        .query_unpaged(
+            format!(
+                "CREATE TABLE IF NOT EXISTS {}.{} (
+            {} {},
+            {} {},
+            {} {},
+            {} {},
+            PRIMARY KEY(({}), {})
+        )",
+                BOILER_SWAP_KEYSPACE,
+                tables::boiler_swap::FAVORITES_BY_ITEM,
+                favorites_by_item::ITEM_ID,
+                favorites_by_item::ITEM_ID_TYPE,
+                favorites_by_item::EMAIL,
+                favorites_by_item::EMAIL_TYPE,
+                favorites_by_item::TITLE,
+                favorites_by_item::TITLE_TYPE,
+                favorites_by_item::NOTIFY,
+                favorites_by_item::NOTIFY_TYPE,
+                favorites_by_item::PARTITION_KEY,
+                favorites_by_item::CLUSTERING_KEY,
             ),
             &[],
         )
@@ -4,6 +4,20 @@ pub mod tables {
     pub mod boiler_swap {
         pub const USERS: &str = "users";
         pub const ITEMS: &str = "items";
+        pub const ITEMS_BY_OWNER: &str = "items_by_owner";
+        pub const ITEMS_BY_EXPIRATION: &str = "items_by_expiration";
+        pub const PASSKEYS: &str = "passkeys";
+        pub const PASSKEYS_BY_USER: &str = "passkeys_by_user";
+        pub const ITEM_PHOTOS: &str = "item_photos";
+        pub const THREADS_BY_USER: &str = "threads_by_user";
+        pub const MESSAGES: &str = "messages";
+        pub const SAVED_SEARCHES: &str = "saved_searches";
+        pub const SAVED_SEARCHES_BY_TYPE: &str = "saved_searches_by_type";
+        pub const ITEM_REPORTS: &str = "item_reports";
+        pub const MODERATION_QUEUE: &str = "moderation_queue";
+        pub const CATALOG: &str = "catalog";
+        pub const FAVORITES_BY_USER: &str = "favorites_by_user";
+        pub const FAVORITES_BY_ITEM: &str = "favorites_by_item";
         pub const CDC: &str = "cdc";
     }
 }
@@ -20,10 +34,49 @@ pub mod columns {
             pub const LOCKED: &str = "locked";
             pub const LOCKED_TYPE: &str = "boolean";
 
+            pub const ROLE: &str = "role";
+            pub const ROLE_TYPE: &str = "tinyint";
+
+            pub const TOTP_SECRET: &str = "totp_secret";
+            pub const TOTP_SECRET_TYPE: &str = "text";
+
+            pub const RECOVERY_CODES: &str = "recovery_codes";
+            pub const RECOVERY_CODES_TYPE: &str = "set<text>";
+
             pub const PRIMARY_KEY: &str = EMAIL;
             pub const TTL: &str = "126144000";
         }
 
+        pub mod passkeys {
+            pub const CREDENTIAL_ID: &str = "credential_id";
+            pub const CREDENTIAL_ID_TYPE: &str = "text";
+
+            pub const EMAIL: &str = "email";
+            pub const EMAIL_TYPE: &str = "text";
+
+            pub const PUBLIC_KEY: &str = "public_key";
+            pub const PUBLIC_KEY_TYPE: &str = "blob";
+
+            pub const SIGN_COUNT: &str = "sign_count";
+            pub const SIGN_COUNT_TYPE: &str = "bigint";
+
+            pub const PRIMARY_KEY: &str = CREDENTIAL_ID;
+        }
+
+        pub mod passkeys_by_user {
+            pub const EMAIL: &str = "email";
+            pub const EMAIL_TYPE: &str = "text";
+
+            pub const CREDENTIAL_ID: &str = "credential_id";
+            pub const CREDENTIAL_ID_TYPE: &str = "text";
+
+            pub const CREATED_AT: &str = "created_at";
+            pub const CREATED_AT_TYPE: &str = "timestamp";
+
+            pub const PARTITION_KEY: &str = EMAIL;
+            pub const CLUSTERING_KEY: &str = CREDENTIAL_ID;
+        }
+
         pub mod items {
             pub const ITEM_ID: &str = "item_id";
             pub const ITEM_ID_TYPE: &str = "uuid";
@@ -49,7 +102,237 @@ pub mod columns {
             pub const EXPIRATION_DATE: &str = "expiration_date";
             pub const EXPIRATION_DATE_TYPE: &str = "date";
 
+            pub const OWNER: &str = "owner";
+            pub const OWNER_TYPE: &str = "text";
+
+            pub const PHOTOS: &str = "photos";
+            pub const PHOTOS_TYPE: &str = "list<uuid>";
+
+            pub const STATUS: &str = "status";
+            pub const STATUS_TYPE: &str = "tinyint";
+
+            pub const HIDDEN: &str = "hidden";
+            pub const HIDDEN_TYPE: &str = "boolean";
+
+            pub const RENEWALS: &str = "renewals";
+            pub const RENEWALS_TYPE: &str = "tinyint";
+
+            pub const CAMPUS: &str = "campus";
+            pub const CAMPUS_TYPE: &str = "text";
+
+            pub const PRIMARY_KEY: &str = ITEM_ID;
+        }
+
+        pub mod item_reports {
+            pub const ITEM_ID: &str = "item_id";
+            pub const ITEM_ID_TYPE: &str = "uuid";
+
+            pub const REPORTER: &str = "reporter";
+            pub const REPORTER_TYPE: &str = "text";
+
+            pub const REASON: &str = "reason";
+            pub const REASON_TYPE: &str = "tinyint";
+
+            pub const PARTITION_KEY: &str = ITEM_ID;
+            pub const CLUSTERING_KEY: &str = REPORTER;
+            pub const TTL: &str = "1814400";
+        }
+
+        pub mod catalog {
+            pub const KIND: &str = "kind";
+            pub const KIND_TYPE: &str = "tinyint";
+
+            pub const CODE: &str = "code";
+            pub const CODE_TYPE: &str = "tinyint";
+
+            pub const NAME: &str = "name";
+            pub const NAME_TYPE: &str = "text";
+
+            pub const LABEL: &str = "label";
+            pub const LABEL_TYPE: &str = "text";
+
+            pub const LATITUDE: &str = "latitude";
+            pub const LATITUDE_TYPE: &str = "double";
+
+            pub const LONGITUDE: &str = "longitude";
+            pub const LONGITUDE_TYPE: &str = "double";
+
+            pub const CAMPUS: &str = "campus";
+            pub const CAMPUS_TYPE: &str = "text";
+
+            pub const PARTITION_KEY: &str = KIND;
+            pub const CLUSTERING_KEY: &str = CODE;
+        }
+
+        pub mod moderation_queue {
+            pub const ITEM_ID: &str = "item_id";
+            pub const ITEM_ID_TYPE: &str = "uuid";
+
+            pub const REPORTS: &str = "reports";
+            pub const REPORTS_TYPE: &str = "bigint";
+
+            pub const QUEUED_AT: &str = "queued_at";
+            pub const QUEUED_AT_TYPE: &str = "timestamp";
+
             pub const PRIMARY_KEY: &str = ITEM_ID;
+            pub const TTL: &str = "1814400";
+        }
+
+        pub mod item_photos {
+            pub const ITEM_ID: &str = "item_id";
+            pub const ITEM_ID_TYPE: &str = "uuid";
+
+            pub const PHOTO_ID: &str = "photo_id";
+            pub const PHOTO_ID_TYPE: &str = "uuid";
+
+            pub const PHOTO: &str = "photo";
+            pub const PHOTO_TYPE: &str = "blob";
+
+            pub const PARTITION_KEY: &str = ITEM_ID;
+            pub const CLUSTERING_KEY: &str = PHOTO_ID;
+        }
+
+        pub mod threads_by_user {
+            pub const EMAIL: &str = "email";
+            pub const EMAIL_TYPE: &str = "text";
+
+            pub const THREAD_ID: &str = "thread_id";
+            pub const THREAD_ID_TYPE: &str = "uuid";
+
+            pub const ITEM_ID: &str = "item_id";
+            pub const ITEM_ID_TYPE: &str = "uuid";
+
+            pub const OTHER_EMAIL: &str = "other_email";
+            pub const OTHER_EMAIL_TYPE: &str = "text";
+
+            pub const ROLE: &str = "role";
+            pub const ROLE_TYPE: &str = "tinyint";
+
+            pub const PARTITION_KEY: &str = EMAIL;
+            pub const CLUSTERING_KEY: &str = THREAD_ID;
+            pub const TTL: &str = "2592000";
+        }
+
+        pub mod messages {
+            pub const THREAD_ID: &str = "thread_id";
+            pub const THREAD_ID_TYPE: &str = "uuid";
+
+            pub const SENT_AT: &str = "sent_at";
+            pub const SENT_AT_TYPE: &str = "timestamp";
+
+            pub const MESSAGE_ID: &str = "message_id";
+            pub const MESSAGE_ID_TYPE: &str = "uuid";
+
+            pub const SENDER: &str = "sender";
+            pub const SENDER_TYPE: &str = "text";
+
+            pub const BODY: &str = "body";
+            pub const BODY_TYPE: &str = "text";
+
+            pub const PARTITION_KEY: &str = THREAD_ID;
+            pub const TTL: &str = "2592000";
+        }
+
+        pub mod favorites_by_user {
+            pub const EMAIL: &str = "email";
+            pub const EMAIL_TYPE: &str = "text";
+
+            pub const ITEM_ID: &str = "item_id";
+            pub const ITEM_ID_TYPE: &str = "uuid";
+
+            pub const PARTITION_KEY: &str = EMAIL;
+            pub const CLUSTERING_KEY: &str = ITEM_ID;
+            pub const TTL: &str = "1814400";
+        }
+
+        pub mod favorites_by_item {
+            pub const ITEM_ID: &str = "item_id";
+            pub const ITEM_ID_TYPE: &str = "uuid";
+
+            pub const EMAIL: &str = "email";
+            pub const EMAIL_TYPE: &str = "text";
+
+            pub const TITLE: &str = "title";
+            pub const TITLE_TYPE: &str = "text";
+
+            pub const NOTIFY: &str = "notify";
+            pub const NOTIFY_TYPE: &str = "boolean";
+
+            pub const PARTITION_KEY: &str = ITEM_ID;
+            pub const CLUSTERING_KEY: &str = EMAIL;
+            pub const TTL: &str = "1814400";
+        }
+
+        pub mod saved_searches {
+            pub const EMAIL: &str = "email";
+            pub const EMAIL_TYPE: &str = "text";
+
+            pub const SEARCH_ID: &str = "search_id";
+            pub const SEARCH_ID_TYPE: &str = "uuid";
+
+            pub const QUERY: &str = "query";
+            pub const QUERY_TYPE: &str = "text";
+
+            pub const ITEM_TYPE: &str = "item_type";
+            pub const ITEM_TYPE_TYPE: &str = "tinyint";
+
+            pub const CONDITION: &str = "condition";
+            pub const CONDITION_TYPE: &str = "tinyint";
+
+            pub const LOCATION: &str = "location";
+            pub const LOCATION_TYPE: &str = "tinyint";
+
+            pub const PARTITION_KEY: &str = EMAIL;
+            pub const CLUSTERING_KEY: &str = SEARCH_ID;
+            pub const TTL: &str = "7776000";
+        }
+
+        pub mod saved_searches_by_type {
+            pub const CAMPUS: &str = "campus";
+            pub const CAMPUS_TYPE: &str = "text";
+
+            pub const ITEM_TYPE: &str = "item_type";
+            pub const ITEM_TYPE_TYPE: &str = "tinyint";
+
+            pub const EMAIL: &str = "email";
+            pub const EMAIL_TYPE: &str = "text";
+
+            pub const SEARCH_ID: &str = "search_id";
+            pub const SEARCH_ID_TYPE: &str = "uuid";
+
+            pub const QUERY: &str = "query";
+            pub const QUERY_TYPE: &str = "text";
+
+            pub const CONDITION: &str = "condition";
+            pub const CONDITION_TYPE: &str = "tinyint";
+
+            pub const LOCATION: &str = "location";
+            pub const LOCATION_TYPE: &str = "tinyint";
+
+            // Searches without a type filter share one partition per campus
+            pub const ANY_ITEM_TYPE: i8 = -1;
+        }
+
+        pub mod items_by_owner {
+            pub const OWNER: &str = "owner";
+            pub const OWNER_TYPE: &str = "text";
+
+            pub const ITEM_ID: &str = "item_id";
+            pub const ITEM_ID_TYPE: &str = "uuid";
+
+            pub const PARTITION_KEY: &str = OWNER;
+            pub const CLUSTERING_KEY: &str = ITEM_ID;
+        }
+
+        pub mod items_by_expiration {
+            pub const EXPIRATION_DATE: &str = "expiration_date";
+            pub const EXPIRATION_DATE_TYPE: &str = "date";
+
+            pub const ITEM_ID: &str = "item_id";
+            pub const ITEM_ID_TYPE: &str = "uuid";
+
+            pub const PARTITION_KEY: &str = EXPIRATION_DATE;
+            pub const CLUSTERING_KEY: &str = ITEM_ID;
         }
     }
 }
@@ -4,7 +4,11 @@ use super::database::{
 };
 use crate::{
     AppError,
-    api::{web::swap::database::convert_db_items, web::swap::models::ItemRow},
+    api::web::swap::{
+        catalog::{Catalog, read_catalog},
+        database::convert_db_items,
+        models::ItemRow,
+    },
     config::{read_secret, try_load},
 };
 use anyhow::Result as anyResult;
@@ -18,16 +22,19 @@ use std::{
     marker::{Send, Sync},
     ops::ControlFlow,
     sync::{
-        Arc,
+        Arc, RwLock,
         atomic::{AtomicUsize, Ordering::Relaxed},
     },
 };
 use tokio::task::JoinHandle;
 use uuid::Uuid;
 
+pub const GEO: &str = "_geo";
+
 pub async fn init_meilisearch(
     database_session: Arc<Session>,
     database_queries: &DatabaseQueries,
+    catalog: Arc<RwLock<Catalog>>,
 ) -> Result<
     (
         Arc<Client>,
@@ -63,6 +70,7 @@ pub async fn init_meilisearch(
             tables::boiler_swap::ITEMS,
             items::ITEM_ID,
             item_counter_clone,
+            catalog,
         )
         .await
     });
@@ -77,6 +85,7 @@ pub async fn reindex(
     index_name: &str,
     item_id_name: &str,
     item_counter: Arc<AtomicUsize>,
+    catalog: Arc<RwLock<Catalog>>,
 ) -> Result<(), AppError> {
     let mut paging_state = PagingState::start();
 
@@ -91,17 +100,19 @@ pub async fn reindex(
 
         let row_vec: Vec<ItemRow> = row_result
             .rows::<ItemRow>()?
+            .filter(|row| {
+                !matches!(
+                    row,
+                    Ok((_, _, _, _, _, _, _, _, _, _, hidden, _)) if *hidden == Some(true)
+                )
+            })
             .collect::<Result<Vec<_>, _>>()?;
 
         item_counter.fetch_add(row_vec.len(), Relaxed);
 
-        add_items(
-            meili_client.clone(),
-            index_name,
-            &convert_db_items(&row_vec),
-            item_id_name,
-        )
-        .await?;
+        let items = convert_db_items(&row_vec, &read_catalog(&catalog));
+
+        add_items(meili_client.clone(), index_name, &items, item_id_name).await?;
 
         match paging_state_response.into_paging_control_flow() {
             ControlFlow::Break(()) => {
@@ -165,7 +176,16 @@ fn init_settings() -> Settings {
         ])
         .with_distinct_attribute(Some(items::ITEM_ID))
         .with_searchable_attributes([items::TITLE, items::DESCRIPTION])
-        .with_filterable_attributes([items::ITEM_TYPE, items::CONDITION, items::LOCATION])
+        .with_filterable_attributes([
+            items::ITEM_TYPE,
+            items::CONDITION,
+            items::LOCATION,
+            items::STATUS,
+            items::EXPIRATION_DATE,
+            items::CAMPUS,
+            GEO,
+        ])
+        .with_sortable_attributes([GEO])
         .with_typo_tolerance(TypoToleranceSettings {
             enabled: Some(true),
             disable_on_attributes: None,
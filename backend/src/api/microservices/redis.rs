@@ -5,7 +5,7 @@ use redis::{
 };
 use std::{sync::Arc, time::Duration};
 
-pub async fn init_redis() -> Result<ConnectionManager, AppError> {
+pub async fn init_redis() -> Result<(Client, ConnectionManager), AppError> {
     let redis_url = try_load::<String>("RUST_REDIS_URL", "redis://redis:6379").unwrap();
 
     let client = Client::open(redis_url)?;
@@ -16,7 +16,7 @@ pub async fn init_redis() -> Result<ConnectionManager, AppError> {
 
     let connection_manager = client.get_connection_manager_with_config(config).await?;
 
-    Ok(connection_manager)
+    Ok((client, connection_manager))
 }
 
 pub async fn insert_id(
@@ -0,0 +1,140 @@
+use super::{
+    cookies::get_cookie,
+    locks::check_forgot_locks,
+    models::{Action, RedisAction, WebsitePath},
+    sessions::{
+        create_auth_redis_account, create_temporary_session, delete_all_sessions,
+        try_get_redis_account,
+    },
+    swap::database::delete_user_records,
+    totp::totp_lock_keys,
+    twofactor::send_email,
+    utilities::{format_verified_result, get_key},
+    verify::check_token_content,
+};
+use crate::{AppError, AppState};
+use axum::http::header::HeaderMap;
+use redis::AsyncTypedCommands;
+use std::sync::Arc;
+use tracing::warn;
+use uuid::Uuid;
+
+// Per-user keys are listed explicitly, since some (like TOTP replay locks) carry a suffix after the email
+async fn purge_account_keys(
+    state: Arc<AppState>,
+    website_path: &WebsitePath,
+    email: &str,
+    item_ids: &[Uuid],
+) -> Result<(), AppError> {
+    let mut keys: Vec<String> = [
+        RedisAction::SessionStore,
+        RedisAction::LockedTime,
+        RedisAction::LockedItems,
+        RedisAction::LockedMessages,
+        RedisAction::LockedAlerts,
+        RedisAction::TotpPending,
+    ]
+    .iter()
+    .map(|action| format!("{}:{}:{}", website_path.as_ref(), action.as_ref(), email))
+    .collect();
+
+    // freeze_account stores the lock timestamp without the website prefix
+    keys.push(format!("{}:{}", RedisAction::LockedTime.as_ref(), email));
+    keys.extend(totp_lock_keys(email));
+    keys.extend(item_ids.iter().map(|item_id| {
+        format!(
+            "{}:{}:{}",
+            website_path.as_ref(),
+            RedisAction::DeletedItem.as_ref(),
+            item_id
+        )
+    }));
+
+    state.redis_connection_manager.clone().del(keys).await?;
+
+    Ok(())
+}
+
+pub async fn start_account_deletion(
+    state: Arc<AppState>,
+    email: &str,
+    hashed_ip: &str,
+    website_path: &WebsitePath,
+) -> Result<HeaderMap, AppError> {
+    check_forgot_locks(state.clone(), hashed_ip, website_path.as_ref(), email).await?;
+
+    create_temporary_session(
+        state.clone(),
+        &None,
+        &create_auth_redis_account(email.to_string(), Action::Login, None),
+        &RedisAction::AccountDelete,
+        &None,
+        &Some(get_key(RedisAction::LockedCode, hashed_ip)),
+        website_path,
+    )
+    .await
+}
+
+pub async fn delete_account(
+    state: Arc<AppState>,
+    headers: &HeaderMap,
+    email: &str,
+    code: &str,
+    hashed_ip: &str,
+    website_path: &WebsitePath,
+) -> Result<(), AppError> {
+    let Some(id) = get_cookie(headers, RedisAction::AccountDelete.as_ref()) else {
+        return Err(AppError::Unauthorized("Unable to verify".to_string()));
+    };
+
+    let Some(verified_result) =
+        format_verified_result(state.clone(), website_path, RedisAction::AccountDelete, id).await?
+    else {
+        return Err(AppError::Unauthorized("Unable to verify".to_string()));
+    };
+
+    check_token_content(&verified_result.redis_action, code)?;
+
+    let redis_account = try_get_redis_account(
+        state.clone(),
+        &verified_result,
+        code,
+        hashed_ip,
+        website_path,
+    )
+    .await?;
+
+    if redis_account.email != email {
+        return Err(AppError::Unauthorized("Unable to verify".to_string()));
+    }
+
+    let item_ids = delete_user_records(state.clone(), email).await?;
+
+    delete_all_sessions(
+        state.clone(),
+        website_path.as_ref(),
+        RedisAction::Session.as_ref(),
+        RedisAction::SessionStore.as_ref(),
+        email,
+    )
+    .await?;
+
+    purge_account_keys(state.clone(), website_path, email, &item_ids).await?;
+
+    let email = email.to_string();
+
+    tokio::spawn(async move {
+        if let Err(e) = send_email(
+            state.clone(),
+            &email,
+            "Account Deleted",
+            "Your account and all of its listings have been deleted.".to_string(),
+        )
+        .await
+        {
+            warn!("Account deletion email failed: {}", e);
+        }
+    });
+
+    Ok(())
+}
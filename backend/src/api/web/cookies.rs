@@ -20,6 +20,9 @@ static COOKIES_TO_CLEAR: Lazy<Vec<&'static str>> = Lazy::new(|| {
         RedisAction::Forgot.as_ref(),
         RedisAction::Update.as_ref(),
         RedisAction::Auth.as_ref(),
+        RedisAction::EmailOld.as_ref(),
+        RedisAction::EmailNew.as_ref(),
+        RedisAction::AccountDelete.as_ref(),
     ]
 });
 
@@ -90,6 +93,16 @@ pub fn get_cookie(headers: &HeaderMap, key: &str) -> Option<String> {
         .map(|cookie| cookie.value().to_string())
 }
 
+pub fn single_cookie(
+    key: &str,
+    value: &str,
+    ttl_seconds: i64,
+    website_path: &WebsitePath,
+) -> HeaderValue {
+    HeaderValue::from_str(&build_cookie(key, value, website_path.as_ref(), ttl_seconds).to_string())
+        .unwrap()
+}
+
 fn build_cookie(
     key: &str,
     value: &str,
@@ -0,0 +1,168 @@
+use super::{
+    cookies::get_cookie,
+    locks::{LockCheck, are_all_locked},
+    models::{Action, RedisAccount, RedisAction, VerifiedTokenResult, WebsitePath},
+    sessions::{
+        create_auth_redis_account, create_temporary_session, migrate_sessions, rotate_session,
+        try_get_redis_account,
+    },
+    swap::database::{get_user, migrate_user},
+    utilities::{format_verified_result, get_key},
+    verify::{check_email, check_token_content},
+};
+use crate::{AppError, AppState};
+use axum::http::header::HeaderMap;
+use std::sync::Arc;
+
+fn create_email_redis_account(email: &str, pending_email: &str) -> RedisAccount {
+    RedisAccount {
+        pending_email: Some(pending_email.to_string()),
+        ..create_auth_redis_account(email.to_string(), Action::Login, None)
+    }
+}
+
+async fn check_email_change_locks(
+    state: Arc<AppState>,
+    hashed_ip: &str,
+    website_path: &WebsitePath,
+    email: &str,
+) -> Result<(), AppError> {
+    if are_all_locked(
+        state.clone(),
+        website_path.as_ref(),
+        email,
+        &[
+            LockCheck {
+                key: &get_key(RedisAction::LockedCode, hashed_ip),
+                check: &state.config.authentication.max_codes,
+            },
+            LockCheck {
+                key: &get_key(RedisAction::LockedVerify, hashed_ip),
+                check: &state.config.authentication.verify_max_attempts,
+            },
+        ],
+    )
+    .await?
+    {
+        return Err(AppError::Unauthorized(
+            "Try again in 30 minutes".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+async fn get_email_change_token(
+    state: Arc<AppState>,
+    headers: &HeaderMap,
+    website_path: &WebsitePath,
+) -> Result<VerifiedTokenResult, AppError> {
+    for redis_action in [RedisAction::EmailNew, RedisAction::EmailOld] {
+        if let Some(id) = get_cookie(headers, redis_action.as_ref())
+            && let Some(verified_result) =
+                format_verified_result(state.clone(), website_path, redis_action, id).await?
+        {
+            return Ok(verified_result);
+        }
+    }
+
+    Err(AppError::Unauthorized("Unable to verify".to_string()))
+}
+
+pub async fn start_email_change(
+    state: Arc<AppState>,
+    email: &str,
+    new_email: &str,
+    hashed_ip: &str,
+    website_path: &WebsitePath,
+) -> Result<HeaderMap, AppError> {
+    check_email(new_email, &state.config.website_specific.campuses)?;
+
+    if new_email == email {
+        return Err(AppError::BadRequest("Email unchanged".to_string()));
+    }
+
+    let campuses = &state.config.website_specific.campuses;
+
+    if campuses.for_email(new_email)?.id != campuses.for_email(email)?.id {
+        return Err(AppError::BadRequest(
+            "Email must stay on the same campus".to_string(),
+        ));
+    }
+
+    check_email_change_locks(state.clone(), hashed_ip, website_path, email).await?;
+
+    if get_user(state.clone(), new_email, website_path)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::BadRequest("Email already in use".to_string()));
+    }
+
+    // The current address confirms first, so a hijacked session cannot move the account alone
+    create_temporary_session(
+        state.clone(),
+        &None,
+        &create_email_redis_account(email, new_email),
+        &RedisAction::EmailOld,
+        &None,
+        &Some(get_key(RedisAction::LockedCode, hashed_ip)),
+        website_path,
+    )
+    .await
+}
+
+pub async fn confirm_email_change(
+    state: Arc<AppState>,
+    headers: &HeaderMap,
+    email: &str,
+    code: &str,
+    hashed_ip: &str,
+    website_path: &WebsitePath,
+) -> Result<HeaderMap, AppError> {
+    let verified_result = get_email_change_token(state.clone(), headers, website_path).await?;
+    check_token_content(&verified_result.redis_action, code)?;
+
+    let redis_account = try_get_redis_account(
+        state.clone(),
+        &verified_result,
+        code,
+        hashed_ip,
+        website_path,
+    )
+    .await?;
+
+    if verified_result.redis_action == RedisAction::EmailOld {
+        let new_email = match redis_account.pending_email {
+            Some(new_email) if redis_account.email == email => new_email,
+            _ => return Err(AppError::Unauthorized("Unable to verify".to_string())),
+        };
+
+        return create_temporary_session(
+            state.clone(),
+            &None,
+            &create_email_redis_account(&new_email, email),
+            &RedisAction::EmailNew,
+            &None,
+            &Some(get_key(RedisAction::LockedCode, hashed_ip)),
+            website_path,
+        )
+        .await;
+    }
+
+    if redis_account.pending_email.as_deref() != Some(email) {
+        return Err(AppError::Unauthorized("Unable to verify".to_string()));
+    }
+
+    let item_ids = migrate_user(state.clone(), email, &redis_account.email).await?;
+    migrate_sessions(
+        state.clone(),
+        website_path,
+        email,
+        &redis_account.email,
+        &item_ids,
+    )
+    .await?;
+
+    rotate_session(state.clone(), headers, website_path).await
+}
@@ -1,15 +1,26 @@
 use super::{
+    account::{delete_account, start_account_deletion},
     cookies::{clear_cookies, remove_cookie},
+    email::{confirm_email_change, start_email_change},
     locks::{
-        check_auth_locks, check_forgot_locks, freeze_account, is_home_locked,
+        change_password, check_auth_locks, check_forgot_locks, freeze_account, is_home_locked,
         prepare_resend_and_check_locks, unfreeze_account,
     },
-    models::{Account, METRICS_ROUTE, PHOTOS_PREFIX, RedisAction, Token, WebsitePath},
+    models::{
+        Account, ITEM_PHOTOS_PREFIX, LinkToken, METRICS_ROUTE, PHOTOS_PREFIX, PasskeyAssertion,
+        PasskeyRegistration, PasswordChange, RedisAction, SWAP_LINKS_PREFIX, Token, WebsitePath,
+    },
+    passkeys::{
+        finish_login, finish_registration, remove_passkey, start_login, start_registration,
+    },
     sessions::{
-        create_forgot_redis_account, create_session, create_temporary_session,
-        try_create_redis_account, try_get_redis_account,
+        create_forgot_redis_account, create_session, create_temporary_session, delete_all_sessions,
+        delete_other_sessions, list_sessions, refresh_session, revoke_session, rotate_session,
+        try_create_redis_account, try_get_redis_account, try_magic_link,
     },
-    utilities::{get_hashed_ip, get_key, get_website_path},
+    swap::database::get_passkeys,
+    totp::{confirm_enrollment, disable_totp, enrollment_qr, start_enrollment},
+    utilities::{get_hashed_ip, get_key, get_session_info, get_website_path},
     verify::{
         check_account, check_email, check_resend, check_token, check_token_content,
         is_request_authorized,
@@ -20,11 +31,14 @@ use crate::{
     metrics::{get_visitors_payload, incr_visitors},
 };
 use axum::{
-    Extension, Json,
-    extract::{ConnectInfo, Request, State},
-    http::{StatusCode, header::HeaderMap},
+    Extension, Form, Json,
+    extract::{ConnectInfo, Path, Query, Request, State},
+    http::{
+        StatusCode,
+        header::{CONTENT_TYPE, HeaderMap, SET_COOKIE},
+    },
     middleware::Next,
-    response::IntoResponse,
+    response::{Html, IntoResponse, Redirect},
 };
 use std::{net::SocketAddr, sync::Arc};
 use tracing::info;
@@ -37,7 +51,10 @@ pub async fn api_token_check(
 ) -> Result<impl IntoResponse, AppError> {
     let path = request.uri().path();
 
-    if path == METRICS_ROUTE {
+    if path == METRICS_ROUTE
+        || path.starts_with(ITEM_PHOTOS_PREFIX)
+        || path.starts_with(SWAP_LINKS_PREFIX)
+    {
         return Ok(next.run(request).await);
     }
 
@@ -49,7 +66,23 @@ pub async fn api_token_check(
 
     is_request_authorized(state.clone(), &headers, &mut request).await?;
 
-    Ok(next.run(request).await)
+    let website_path = request
+        .extensions()
+        .get::<String>()
+        .map(|label| get_website_path(label));
+
+    let mut response = next.run(request).await;
+
+    // Handlers that already set cookies have logged in, out or rotated the session
+    if let Some(website_path) = website_path
+        && response.status().is_success()
+        && !response.headers().contains_key(SET_COOKIE)
+        && let Some(cookie) = refresh_session(state.clone(), &headers, &website_path).await?
+    {
+        response.headers_mut().append(SET_COOKIE, cookie);
+    }
+
+    Ok(response)
 }
 
 pub async fn forgot_handler(
@@ -59,7 +92,7 @@ pub async fn forgot_handler(
     State(state): State<Arc<AppState>>,
     Json(payload): Json<Token>,
 ) -> Result<impl IntoResponse, AppError> {
-    check_email(&payload.token)?;
+    check_email(&payload.token, &state.config.website_specific.campuses)?;
 
     let website_path = get_website_path(&label);
     let hashed_ip = get_hashed_ip(&headers, address.ip());
@@ -100,6 +133,258 @@ pub async fn delete_handler(
     Ok((StatusCode::OK, clear_cookies(&label)).into_response())
 }
 
+pub async fn sessions_handler(
+    Extension(label): Extension<String>,
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let website_path = get_website_path(&label);
+
+    let verified_result = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &website_path,
+    )
+    .await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(
+            list_sessions(
+                state.clone(),
+                &website_path,
+                &verified_result
+                    .serialized_account
+                    .expect("session creation faulty"),
+                &verified_result.id,
+            )
+            .await?,
+        ),
+    )
+        .into_response())
+}
+
+pub async fn revoke_session_handler(
+    Extension(label): Extension<String>,
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let website_path = get_website_path(&label);
+
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &website_path,
+    )
+    .await?
+    .serialized_account;
+
+    revoke_session(
+        state.clone(),
+        &website_path,
+        &email.expect("session creation faulty"),
+        &session_id,
+    )
+    .await?;
+
+    Ok((StatusCode::OK).into_response())
+}
+
+pub async fn revoke_all_sessions_handler(
+    Extension(label): Extension<String>,
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let website_path = get_website_path(&label);
+
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &website_path,
+    )
+    .await?
+    .serialized_account;
+
+    delete_all_sessions(
+        state.clone(),
+        website_path.as_ref(),
+        RedisAction::Session.as_ref(),
+        RedisAction::SessionStore.as_ref(),
+        &email.expect("session creation faulty"),
+    )
+    .await?;
+
+    Ok((StatusCode::OK, clear_cookies(&label)).into_response())
+}
+
+pub async fn change_password_handler(
+    Extension(label): Extension<String>,
+    headers: HeaderMap,
+    ConnectInfo(address): ConnectInfo<SocketAddr>,
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<PasswordChange>,
+) -> Result<impl IntoResponse, AppError> {
+    let website_path = get_website_path(&label);
+
+    let verified_result = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &website_path,
+    )
+    .await?;
+    let email = verified_result
+        .serialized_account
+        .expect("session creation faulty");
+
+    change_password(
+        state.clone(),
+        &email,
+        &get_hashed_ip(&headers, address.ip()),
+        &payload,
+        &website_path,
+    )
+    .await?;
+
+    delete_other_sessions(state.clone(), &website_path, &email, &verified_result.id).await?;
+
+    Ok((
+        StatusCode::OK,
+        rotate_session(state.clone(), &headers, &website_path).await?,
+    )
+        .into_response())
+}
+
+pub async fn change_email_handler(
+    Extension(label): Extension<String>,
+    headers: HeaderMap,
+    ConnectInfo(address): ConnectInfo<SocketAddr>,
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<Token>,
+) -> Result<impl IntoResponse, AppError> {
+    let website_path = get_website_path(&label);
+
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &website_path,
+    )
+    .await?
+    .serialized_account;
+
+    Ok((
+        StatusCode::OK,
+        start_email_change(
+            state.clone(),
+            &email.expect("session creation faulty"),
+            &payload.token,
+            &get_hashed_ip(&headers, address.ip()),
+            &website_path,
+        )
+        .await?,
+    )
+        .into_response())
+}
+
+pub async fn confirm_email_handler(
+    Extension(label): Extension<String>,
+    headers: HeaderMap,
+    ConnectInfo(address): ConnectInfo<SocketAddr>,
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<Token>,
+) -> Result<impl IntoResponse, AppError> {
+    let website_path = get_website_path(&label);
+
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &website_path,
+    )
+    .await?
+    .serialized_account;
+
+    Ok((
+        StatusCode::OK,
+        confirm_email_change(
+            state.clone(),
+            &headers,
+            &email.expect("session creation faulty"),
+            &payload.token,
+            &get_hashed_ip(&headers, address.ip()),
+            &website_path,
+        )
+        .await?,
+    )
+        .into_response())
+}
+
+pub async fn start_account_deletion_handler(
+    Extension(label): Extension<String>,
+    headers: HeaderMap,
+    ConnectInfo(address): ConnectInfo<SocketAddr>,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let website_path = get_website_path(&label);
+
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &website_path,
+    )
+    .await?
+    .serialized_account;
+
+    Ok((
+        StatusCode::OK,
+        start_account_deletion(
+            state.clone(),
+            &email.expect("session creation faulty"),
+            &get_hashed_ip(&headers, address.ip()),
+            &website_path,
+        )
+        .await?,
+    )
+        .into_response())
+}
+
+pub async fn delete_account_handler(
+    Extension(label): Extension<String>,
+    headers: HeaderMap,
+    ConnectInfo(address): ConnectInfo<SocketAddr>,
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<Token>,
+) -> Result<impl IntoResponse, AppError> {
+    let website_path = get_website_path(&label);
+
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &website_path,
+    )
+    .await?
+    .serialized_account;
+
+    delete_account(
+        state.clone(),
+        &headers,
+        &email.expect("session creation faulty"),
+        &payload.token,
+        &get_hashed_ip(&headers, address.ip()),
+        &website_path,
+    )
+    .await?;
+
+    Ok((StatusCode::OK, clear_cookies(&label)).into_response())
+}
+
 pub async fn verify_handler(
     Extension(label): Extension<String>,
     headers: HeaderMap,
@@ -160,7 +445,59 @@ pub async fn verify_handler(
 
     Ok((
         StatusCode::OK,
-        create_session(state.clone(), &redis_account, &website_path).await?,
+        create_session(
+            state.clone(),
+            &redis_account,
+            get_session_info(&headers, address.ip()),
+            &website_path,
+        )
+        .await?,
+    )
+        .into_response())
+}
+
+// Mail scanners and link previews follow GET requests, so only the form POST spends the token
+pub async fn magic_link_page_handler(Query(payload): Query<LinkToken>) -> impl IntoResponse {
+    let token = payload
+        .token
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+
+    Html(format!(
+        "<!doctype html><html><head><meta charset=\"utf-8\"><meta name=\"viewport\" content=\"width=device-width, initial-scale=1\"><title>Sign in</title></head><body><form method=\"post\"><input type=\"hidden\" name=\"token\" value=\"{}\"><button type=\"submit\">Continue</button></form></body></html>",
+        token
+    ))
+}
+
+pub async fn magic_link_handler(
+    headers: HeaderMap,
+    ConnectInfo(address): ConnectInfo<SocketAddr>,
+    State(state): State<Arc<AppState>>,
+    Form(payload): Form<LinkToken>,
+) -> Result<impl IntoResponse, AppError> {
+    let (cookies, redis_action) = try_magic_link(
+        state.clone(),
+        &payload.token,
+        &get_hashed_ip(&headers, address.ip()),
+        get_session_info(&headers, address.ip()),
+    )
+    .await?;
+
+    let destination = match redis_action {
+        RedisAction::Update => "/auth/verify/update?reset=true",
+        _ => "",
+    };
+
+    Ok((
+        cookies,
+        Redirect::to(&format!(
+            "{}/{}{}",
+            state.config.server.svelte_url,
+            WebsitePath::BoilerSwap.as_ref(),
+            destination
+        )),
     )
         .into_response())
 }
@@ -177,7 +514,7 @@ pub async fn authenticate_handler(
     info!("1");
     check_auth_locks(state.clone(), &hashed_ip, website_path.as_ref(), &payload).await?;
     info!("2");
-    check_account(&payload)?;
+    check_account(&payload, &state.config.website_specific.campuses)?;
     info!("3");
 
     let redis_account =
@@ -244,6 +581,249 @@ pub async fn resend_handler(
         .into_response())
 }
 
+pub async fn totp_enroll_handler(
+    Extension(label): Extension<String>,
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &get_website_path(&label),
+    )
+    .await?
+    .serialized_account;
+
+    Ok((
+        StatusCode::OK,
+        Json(start_enrollment(state.clone(), &email.expect("session creation faulty")).await?),
+    )
+        .into_response())
+}
+
+pub async fn totp_qr_handler(
+    Extension(label): Extension<String>,
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &get_website_path(&label),
+    )
+    .await?
+    .serialized_account;
+
+    Ok((
+        StatusCode::OK,
+        [(CONTENT_TYPE, "image/png")],
+        enrollment_qr(state.clone(), &email.expect("session creation faulty")).await?,
+    )
+        .into_response())
+}
+
+pub async fn totp_confirm_handler(
+    Extension(label): Extension<String>,
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<Token>,
+) -> Result<impl IntoResponse, AppError> {
+    let website_path = get_website_path(&label);
+
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &website_path,
+    )
+    .await?
+    .serialized_account;
+
+    let recovery_codes = confirm_enrollment(
+        state.clone(),
+        &email.expect("session creation faulty"),
+        &payload.token,
+    )
+    .await?;
+
+    Ok((
+        StatusCode::OK,
+        rotate_session(state.clone(), &headers, &website_path).await?,
+        Json(recovery_codes),
+    )
+        .into_response())
+}
+
+pub async fn totp_disable_handler(
+    Extension(label): Extension<String>,
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<Token>,
+) -> Result<impl IntoResponse, AppError> {
+    let website_path = get_website_path(&label);
+
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &website_path,
+    )
+    .await?
+    .serialized_account;
+
+    disable_totp(
+        state.clone(),
+        &email.expect("session creation faulty"),
+        &payload.token,
+    )
+    .await?;
+
+    Ok((
+        StatusCode::OK,
+        rotate_session(state.clone(), &headers, &website_path).await?,
+    )
+        .into_response())
+}
+
+pub async fn passkey_register_options_handler(
+    Extension(label): Extension<String>,
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &get_website_path(&label),
+    )
+    .await?
+    .serialized_account;
+
+    Ok((
+        StatusCode::OK,
+        Json(start_registration(state.clone(), &email.expect("session creation faulty")).await?),
+    )
+        .into_response())
+}
+
+pub async fn passkey_register_handler(
+    Extension(label): Extension<String>,
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<PasskeyRegistration>,
+) -> Result<impl IntoResponse, AppError> {
+    let website_path = get_website_path(&label);
+
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &website_path,
+    )
+    .await?
+    .serialized_account;
+
+    let passkey = finish_registration(
+        state.clone(),
+        &email.expect("session creation faulty"),
+        payload,
+    )
+    .await?;
+
+    Ok((
+        StatusCode::OK,
+        rotate_session(state.clone(), &headers, &website_path).await?,
+        Json(passkey),
+    )
+        .into_response())
+}
+
+pub async fn passkey_login_options_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<Token>,
+) -> Result<impl IntoResponse, AppError> {
+    check_email(&payload.token, &state.config.website_specific.campuses)?;
+
+    Ok((
+        StatusCode::OK,
+        Json(start_login(state.clone(), &payload.token).await?),
+    )
+        .into_response())
+}
+
+pub async fn passkey_login_handler(
+    headers: HeaderMap,
+    ConnectInfo(address): ConnectInfo<SocketAddr>,
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<PasskeyAssertion>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok((
+        StatusCode::OK,
+        finish_login(
+            state.clone(),
+            &get_hashed_ip(&headers, address.ip()),
+            get_session_info(&headers, address.ip()),
+            payload,
+        )
+        .await?,
+    )
+        .into_response())
+}
+
+pub async fn passkeys_handler(
+    Extension(label): Extension<String>,
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &get_website_path(&label),
+    )
+    .await?
+    .serialized_account;
+
+    Ok((
+        StatusCode::OK,
+        Json(get_passkeys(state.clone(), &email.expect("session creation faulty")).await?),
+    )
+        .into_response())
+}
+
+pub async fn delete_passkey_handler(
+    Extension(label): Extension<String>,
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Path(credential_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let website_path = get_website_path(&label);
+
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &website_path,
+    )
+    .await?
+    .serialized_account;
+
+    remove_passkey(
+        state.clone(),
+        &email.expect("session creation faulty"),
+        &credential_id,
+    )
+    .await?;
+
+    Ok((
+        StatusCode::OK,
+        rotate_session(state.clone(), &headers, &website_path).await?,
+    )
+        .into_response())
+}
+
 pub async fn visitors_handler(
     headers: HeaderMap,
     ConnectInfo(address): ConnectInfo<SocketAddr>,
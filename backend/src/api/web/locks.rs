@@ -1,10 +1,16 @@
 use super::{
-    models::{Account, Action, RedisAccount, RedisAction, VerifiedTokenResult, WebsitePath},
+    models::{
+        Account, Action, PasswordChange, RedisAccount, RedisAction, VerifiedTokenResult,
+        WebsitePath,
+    },
     sessions::delete_all_sessions,
-    swap::database::{check_lock, get_user, unlock_account, update_lock},
+    swap::{
+        database::{check_lock, get_user, get_user_role, unlock_account, update_lock},
+        models::UserRole,
+    },
     twofactor::generate_code,
     utilities::get_key,
-    verify::hash_password,
+    verify::{hash_password, validate_password, verify_password},
 };
 use crate::{
     AppError, AppState,
@@ -85,6 +91,10 @@ pub async fn unfreeze_account(
     password: &str,
     website_path: &WebsitePath,
 ) -> Result<(), AppError> {
+    if get_user_role(state.clone(), email, website_path).await? == Some(UserRole::Banned) {
+        return Err(AppError::Unauthorized("Account banned".to_string()));
+    }
+
     unlock_account(
         state.clone(),
         email,
@@ -100,6 +110,70 @@ pub async fn unfreeze_account(
     Ok(())
 }
 
+pub async fn change_password(
+    state: Arc<AppState>,
+    email: &str,
+    hashed_ip: &str,
+    payload: &PasswordChange,
+    website_path: &WebsitePath,
+) -> Result<(), AppError> {
+    let lock_key = get_key(RedisAction::LockedAuth, hashed_ip);
+
+    if is_redis_locked(
+        state.clone(),
+        website_path.as_ref(),
+        &lock_key,
+        email,
+        &state.config.authentication.auth_max_attempts,
+    )
+    .await?
+    {
+        return Err(AppError::Unauthorized(
+            "Try again in 30 minutes".to_string(),
+        ));
+    }
+
+    validate_password(&payload.new_password).map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+    // A frozen account is mid-reset, and only the Forgot flow may finish it
+    let Some((password_hash, false)) = get_user(state.clone(), email, website_path).await? else {
+        return Err(AppError::Unauthorized("Unable to verify".to_string()));
+    };
+
+    if !spawn_blocking({
+        let plaintext = payload.current_password.to_owned();
+        move || verify_password(&plaintext, &password_hash)
+    })
+    .await?
+    {
+        increment_lock_key(
+            state.clone(),
+            website_path.as_ref(),
+            &lock_key,
+            email,
+            &state.config.authentication.auth_lock_duration_seconds,
+            &state.config.authentication.auth_max_attempts,
+        )
+        .await?;
+
+        return Err(AppError::Unauthorized("Invalid Credentials".to_string()));
+    }
+
+    unlock_account(
+        state.clone(),
+        email,
+        &spawn_blocking({
+            let password_owned = payload.new_password.to_owned();
+            move || hash_password(&password_owned)
+        })
+        .await?,
+        website_path,
+    )
+    .await?;
+
+    Ok(())
+}
+
 pub async fn check_locks(
     state: Arc<AppState>,
     email: &str,
@@ -1,9 +1,13 @@
+pub mod account;
 pub mod cookies;
+pub mod email;
 pub mod handlers;
 pub mod locks;
 pub mod models;
+pub mod passkeys;
 pub mod sessions;
 pub mod swap;
+pub mod totp;
 pub mod twofactor;
 pub mod utilities;
 pub mod verify;
@@ -1,11 +1,15 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use strum_macros::{AsRefStr, EnumString};
+use uuid::Uuid;
 
 #[derive(Serialize, Deserialize)]
 pub struct Account {
     pub email: String,
     pub password: String,
     pub action: Action,
+    #[serde(default)]
+    pub remember: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -16,6 +20,10 @@ pub struct RedisAccount {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub issued_timestamp: Option<i64>,
     pub password_hash: Option<String>,
+    #[serde(default)]
+    pub remember: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_email: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -55,6 +63,18 @@ pub enum RedisAction {
     #[strum(serialize = "sessions")]
     SessionStore,
 
+    #[strum(serialize = "session_info")]
+    SessionInfo,
+
+    #[strum(serialize = "email_old_id")]
+    EmailOld,
+
+    #[strum(serialize = "email_new_id")]
+    EmailNew,
+
+    #[strum(serialize = "account_delete_id")]
+    AccountDelete,
+
     #[strum(serialize = "verify_lock")]
     LockedVerify,
 
@@ -75,11 +95,147 @@ pub enum RedisAction {
 
     #[strum(serialize = "metric")]
     Metric,
+
+    #[strum(serialize = "message")]
+    Message,
+
+    #[strum(serialize = "message_lock")]
+    LockedMessages,
+
+    #[strum(serialize = "alert_lock")]
+    LockedAlerts,
+
+    #[strum(serialize = "search_lock")]
+    LockedSearch,
+
+    #[strum(serialize = "totp_pending")]
+    TotpPending,
+
+    #[strum(serialize = "totp_lock")]
+    LockedTotp,
+
+    #[strum(serialize = "passkey_challenge")]
+    PasskeyChallenge,
 }
 
 #[derive(Deserialize)]
 pub struct DummyClaims {}
 
+#[derive(Serialize, Deserialize)]
+pub struct LinkClaims {
+    pub sub: String,
+    pub action: String,
+    pub id: Uuid,
+    pub exp: usize,
+}
+
+#[derive(Deserialize)]
+pub struct PasswordChange {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SessionInfo {
+    pub created_at: DateTime<Utc>,
+    pub user_agent: String,
+    pub ip: String,
+    #[serde(default)]
+    pub remember: bool,
+}
+
+#[derive(Serialize)]
+pub struct DeviceSession {
+    pub session_id: String,
+    #[serde(flatten)]
+    pub info: SessionInfo,
+    pub current: bool,
+}
+
+#[derive(Serialize)]
+pub struct PasskeyOptions {
+    pub challenge_id: String,
+    pub challenge: String,
+    pub rp_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    pub credential_ids: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct PasskeyRegistration {
+    pub challenge_id: String,
+    pub credential_id: String,
+    pub client_data_json: String,
+    pub attestation_object: String,
+}
+
+#[derive(Deserialize)]
+pub struct PasskeyAssertion {
+    pub challenge_id: String,
+    pub credential_id: String,
+    pub client_data_json: String,
+    pub authenticator_data: String,
+    pub signature: String,
+    #[serde(default)]
+    pub remember: bool,
+}
+
+#[derive(Deserialize)]
+pub struct ClientData {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub challenge: String,
+    pub origin: String,
+}
+
+#[derive(Serialize)]
+pub struct Passkey {
+    pub credential_id: String,
+    pub created_at: DateTime<Utc>,
+}
+
+pub type PasskeyRow = (String, Vec<u8>, i64);
+
+pub type UserRow = (
+    String,
+    bool,
+    Option<i8>,
+    Option<String>,
+    Option<Vec<String>>,
+);
+
+#[derive(Serialize)]
+pub struct TotpEnrollment {
+    pub secret: String,
+    pub uri: String,
+}
+
+#[derive(Serialize)]
+pub struct RecoveryCodes {
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct LinkToken {
+    pub token: String,
+}
+
+#[derive(EnumString, AsRefStr, PartialEq)]
+pub enum LinkAction {
+    #[strum(serialize = "unsubscribe")]
+    Unsubscribe,
+
+    #[strum(serialize = "renew")]
+    Renew,
+
+    #[strum(serialize = "auth")]
+    Auth,
+
+    #[strum(serialize = "forgot")]
+    Forgot,
+}
+
 #[derive(EnumString, AsRefStr, PartialEq, Clone)]
 pub enum WebsitePath {
     #[strum(serialize = "swap")]
@@ -123,8 +279,33 @@ pub enum WebsiteRoute {
 
     #[strum(serialize = "resend")]
     Resend,
+
+    #[strum(serialize = "links")]
+    Links,
+
+    #[strum(serialize = "sessions")]
+    Sessions,
+
+    #[strum(serialize = "password")]
+    Password,
+
+    #[strum(serialize = "email")]
+    Email,
+
+    #[strum(serialize = "account")]
+    Account,
+
+    #[strum(serialize = "totp")]
+    Totp,
+
+    #[strum(serialize = "passkeys")]
+    Passkeys,
 }
 
 pub const METRICS_ROUTE: &str = "/metrics";
 
 pub const PHOTOS_PREFIX: &str = "/photos/";
+
+pub const SWAP_LINKS_PREFIX: &str = "/swap/links/";
+
+pub const ITEM_PHOTOS_PREFIX: &str = "/photos/items/";
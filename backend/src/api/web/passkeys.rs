@@ -0,0 +1,431 @@
+use super::{
+    locks::{increment_lock_key, is_redis_locked},
+    models::{
+        Action, ClientData, Passkey, PasskeyAssertion, PasskeyOptions, PasskeyRegistration,
+        RedisAccount, RedisAction, SessionInfo, WebsitePath,
+    },
+    sessions::{create_auth_redis_account, create_session},
+    swap::database::{
+        delete_passkey, get_passkey, get_passkeys, get_user, insert_passkey, update_sign_count,
+    },
+    utilities::get_key,
+};
+use crate::{
+    AppError, AppState,
+    api::microservices::redis::{insert_id, remove_id, try_get},
+};
+use axum::http::HeaderMap;
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::Utc;
+use ciborium::Value;
+use p256::ecdsa::{Signature, VerifyingKey, signature::Verifier};
+use rand::{RngCore, thread_rng};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use uuid::Uuid;
+
+const CHALLENGE_BYTES: usize = 32;
+
+const FLAG_USER_PRESENT: u8 = 0x01;
+
+const FLAG_ATTESTED_CREDENTIAL: u8 = 0x40;
+
+// rpIdHash (32) + flags (1) + signCount (4)
+const AUTH_DATA_HEADER: usize = 37;
+
+// aaguid (16) + credentialIdLength (2)
+const ATTESTED_HEADER: usize = 18;
+
+const COSE_ALG_ES256: i128 = -7;
+
+const MAX_PASSKEYS: usize = 10;
+
+struct AuthData {
+    flags: u8,
+    sign_count: u32,
+}
+
+fn relying_party(origin: &str) -> &str {
+    let host = origin.split_once("://").map_or(origin, |(_, rest)| rest);
+
+    host.split(['/', ':']).next().unwrap_or(host)
+}
+
+fn decode(value: &str) -> Result<Vec<u8>, &'static str> {
+    URL_SAFE_NO_PAD
+        .decode(value.trim_end_matches('='))
+        .map_err(|_| "Malformed passkey payload")
+}
+
+fn check_client_data(
+    client_data_json: &[u8],
+    kind: &str,
+    challenge: &str,
+    origin: &str,
+) -> Result<(), &'static str> {
+    let client_data: ClientData =
+        serde_json::from_slice(client_data_json).map_err(|_| "Malformed client data")?;
+
+    if client_data.kind != kind
+        || client_data.challenge.trim_end_matches('=') != challenge
+        || client_data.origin != origin
+    {
+        return Err("Unable to verify");
+    }
+
+    Ok(())
+}
+
+fn parse_auth_data(auth_data: &[u8], rp_id: &str) -> Result<AuthData, &'static str> {
+    if auth_data.len() < AUTH_DATA_HEADER {
+        return Err("Malformed authenticator data");
+    }
+
+    if auth_data[..32] != Sha256::digest(rp_id.as_bytes())[..] {
+        return Err("Unable to verify");
+    }
+
+    let flags = auth_data[32];
+    if flags & FLAG_USER_PRESENT == 0 {
+        return Err("Unable to verify");
+    }
+
+    Ok(AuthData {
+        flags,
+        sign_count: u32::from_be_bytes([
+            auth_data[33],
+            auth_data[34],
+            auth_data[35],
+            auth_data[36],
+        ]),
+    })
+}
+
+fn map_get<'a>(map: &'a [(Value, Value)], key: &Value) -> Option<&'a Value> {
+    map.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+}
+
+fn parse_cose_key(cose_key: &[u8]) -> Result<Vec<u8>, &'static str> {
+    let Ok(Value::Map(map)) = ciborium::from_reader::<Value, _>(cose_key) else {
+        return Err("Malformed public key");
+    };
+
+    match map_get(&map, &Value::Integer(3.into())) {
+        Some(Value::Integer(alg)) if i128::from(*alg) == COSE_ALG_ES256 => {}
+        _ => return Err("Only ES256 passkeys are supported"),
+    }
+
+    let (Some(Value::Bytes(x)), Some(Value::Bytes(y))) = (
+        map_get(&map, &Value::Integer((-2).into())),
+        map_get(&map, &Value::Integer((-3).into())),
+    ) else {
+        return Err("Malformed public key");
+    };
+
+    let public_key = [&[0x04], x.as_slice(), y.as_slice()].concat();
+    VerifyingKey::from_sec1_bytes(&public_key).map_err(|_| "Malformed public key")?;
+
+    Ok(public_key)
+}
+
+fn parse_attestation(
+    attestation_object: &[u8],
+    rp_id: &str,
+) -> Result<(Vec<u8>, Vec<u8>, u32), &'static str> {
+    let Ok(Value::Map(map)) = ciborium::from_reader::<Value, _>(attestation_object) else {
+        return Err("Malformed attestation");
+    };
+
+    let Some(Value::Bytes(auth_data)) = map_get(&map, &Value::Text("authData".to_string())) else {
+        return Err("Malformed attestation");
+    };
+
+    let parsed = parse_auth_data(auth_data, rp_id)?;
+    if parsed.flags & FLAG_ATTESTED_CREDENTIAL == 0 {
+        return Err("Malformed attestation");
+    }
+
+    let credential_start = AUTH_DATA_HEADER + ATTESTED_HEADER;
+    if auth_data.len() < credential_start {
+        return Err("Malformed attestation");
+    }
+
+    let credential_length = u16::from_be_bytes([
+        auth_data[credential_start - 2],
+        auth_data[credential_start - 1],
+    ]) as usize;
+    let Some(credential_id) = auth_data.get(credential_start..credential_start + credential_length)
+    else {
+        return Err("Malformed attestation");
+    };
+
+    Ok((
+        credential_id.to_vec(),
+        parse_cose_key(&auth_data[credential_start + credential_length..])?,
+        parsed.sign_count,
+    ))
+}
+
+fn verify_assertion(
+    public_key: &[u8],
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    signature: &[u8],
+) -> Result<(), &'static str> {
+    let verifying_key =
+        VerifyingKey::from_sec1_bytes(public_key).map_err(|_| "Malformed public key")?;
+    let signature = Signature::from_der(signature).map_err(|_| "Malformed signature")?;
+
+    let signed = [
+        authenticator_data,
+        Sha256::digest(client_data_json).as_slice(),
+    ]
+    .concat();
+
+    verifying_key
+        .verify(&signed, &signature)
+        .map_err(|_| "Unable to verify")
+}
+
+fn challenge_key(challenge_id: &str) -> String {
+    format!(
+        "{}:{}:{}",
+        WebsitePath::BoilerSwap.as_ref(),
+        RedisAction::PasskeyChallenge.as_ref(),
+        challenge_id
+    )
+}
+
+async fn issue_challenge(
+    state: Arc<AppState>,
+    email: &str,
+    user_id: Option<String>,
+    credential_ids: Vec<String>,
+) -> Result<PasskeyOptions, AppError> {
+    let mut challenge_bytes = [0u8; CHALLENGE_BYTES];
+    thread_rng().fill_bytes(&mut challenge_bytes);
+
+    let challenge = URL_SAFE_NO_PAD.encode(challenge_bytes);
+    let challenge_id = Uuid::new_v4().to_string();
+
+    insert_id(
+        state.clone(),
+        &challenge_key(&challenge_id),
+        &format!("{}:{}", challenge, email),
+        state
+            .config
+            .session
+            .temporary_session_duration_seconds
+            .into(),
+    )
+    .await?;
+
+    Ok(PasskeyOptions {
+        challenge_id,
+        challenge,
+        rp_id: relying_party(&state.config.server.svelte_url).to_string(),
+        user_id,
+        credential_ids,
+    })
+}
+
+async fn take_challenge(
+    state: Arc<AppState>,
+    challenge_id: &str,
+) -> Result<(String, String), AppError> {
+    let key = challenge_key(challenge_id);
+
+    let Some(stored) = try_get(state.clone(), &key).await? else {
+        return Err(AppError::Unauthorized("Unable to verify".to_string()));
+    };
+
+    remove_id(state.clone(), &key).await?;
+
+    stored
+        .split_once(':')
+        .map(|(challenge, email)| (challenge.to_string(), email.to_string()))
+        .ok_or(AppError::Unauthorized("Unable to verify".to_string()))
+}
+
+async fn credential_ids(state: Arc<AppState>, email: &str) -> Result<Vec<String>, AppError> {
+    Ok(get_passkeys(state.clone(), email)
+        .await?
+        .into_iter()
+        .map(|passkey| passkey.credential_id)
+        .collect())
+}
+
+pub async fn start_registration(
+    state: Arc<AppState>,
+    email: &str,
+) -> Result<PasskeyOptions, AppError> {
+    let existing = credential_ids(state.clone(), email).await?;
+
+    if existing.len() >= MAX_PASSKEYS {
+        return Err(AppError::BadRequest("Too many passkeys".to_string()));
+    }
+
+    issue_challenge(
+        state.clone(),
+        email,
+        Some(URL_SAFE_NO_PAD.encode(Sha256::digest(email.as_bytes()))),
+        existing,
+    )
+    .await
+}
+
+pub async fn finish_registration(
+    state: Arc<AppState>,
+    email: &str,
+    payload: PasskeyRegistration,
+) -> Result<Passkey, AppError> {
+    let (challenge, challenge_email) = take_challenge(state.clone(), &payload.challenge_id).await?;
+
+    if challenge_email != email {
+        return Err(AppError::Unauthorized("Unable to verify".to_string()));
+    }
+
+    let origin = &state.config.server.svelte_url;
+
+    let (credential_id, public_key, sign_count) = decode(&payload.client_data_json)
+        .and_then(|client_data_json| {
+            check_client_data(&client_data_json, "webauthn.create", &challenge, origin)
+        })
+        .and_then(|_| decode(&payload.attestation_object))
+        .and_then(|attestation_object| {
+            parse_attestation(&attestation_object, relying_party(origin))
+        })
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+    let credential_id = URL_SAFE_NO_PAD.encode(credential_id);
+    if credential_id != payload.credential_id.trim_end_matches('=') {
+        return Err(AppError::BadRequest("Malformed attestation".to_string()));
+    }
+
+    if get_passkey(state.clone(), &credential_id).await?.is_some() {
+        return Err(AppError::BadRequest(
+            "Passkey already registered".to_string(),
+        ));
+    }
+
+    insert_passkey(
+        state.clone(),
+        email,
+        &credential_id,
+        public_key,
+        sign_count.into(),
+    )
+    .await?;
+
+    Ok(Passkey {
+        credential_id,
+        created_at: Utc::now(),
+    })
+}
+
+pub async fn start_login(state: Arc<AppState>, email: &str) -> Result<PasskeyOptions, AppError> {
+    let existing = credential_ids(state.clone(), email).await?;
+
+    issue_challenge(state.clone(), email, None, existing).await
+}
+
+pub async fn finish_login(
+    state: Arc<AppState>,
+    hashed_ip: &str,
+    session_info: SessionInfo,
+    payload: PasskeyAssertion,
+) -> Result<HeaderMap, AppError> {
+    let (challenge, email) = take_challenge(state.clone(), &payload.challenge_id).await?;
+    let lock_key = get_key(RedisAction::LockedAuth, hashed_ip);
+
+    if is_redis_locked(
+        state.clone(),
+        WebsitePath::BoilerSwap.as_ref(),
+        &lock_key,
+        &email,
+        &state.config.authentication.auth_max_attempts,
+    )
+    .await?
+    {
+        return Err(AppError::Unauthorized(
+            "Try again in 30 minutes".to_string(),
+        ));
+    }
+
+    let credential_id = payload.credential_id.trim_end_matches('=');
+
+    let verified = match get_passkey(state.clone(), credential_id).await? {
+        Some((owner, public_key, stored_count)) if owner == email => {
+            let origin = &state.config.server.svelte_url;
+
+            decode(&payload.client_data_json)
+                .and_then(|client_data_json| {
+                    check_client_data(&client_data_json, "webauthn.get", &challenge, origin)?;
+                    let authenticator_data = decode(&payload.authenticator_data)?;
+                    let parsed = parse_auth_data(&authenticator_data, relying_party(origin))?;
+
+                    verify_assertion(
+                        &public_key,
+                        &authenticator_data,
+                        &client_data_json,
+                        &decode(&payload.signature)?,
+                    )?;
+
+                    // A counter that fails to advance means the credential was cloned
+                    let sign_count = i64::from(parsed.sign_count);
+                    if (sign_count > 0 || stored_count > 0) && sign_count <= stored_count {
+                        return Err("Unable to verify");
+                    }
+
+                    Ok(sign_count)
+                })
+                .ok()
+        }
+        _ => None,
+    };
+
+    let Some(sign_count) = verified else {
+        increment_lock_key(
+            state.clone(),
+            WebsitePath::BoilerSwap.as_ref(),
+            &lock_key,
+            &email,
+            &state.config.authentication.auth_lock_duration_seconds,
+            &state.config.authentication.auth_max_attempts,
+        )
+        .await?;
+
+        return Err(AppError::Unauthorized("Unable to verify".to_string()));
+    };
+
+    match get_user(state.clone(), &email, &WebsitePath::BoilerSwap).await? {
+        Some((_, false)) => {}
+        _ => return Err(AppError::Unauthorized("Unable to verify".to_string())),
+    }
+
+    update_sign_count(state.clone(), credential_id, sign_count).await?;
+
+    create_session(
+        state.clone(),
+        &RedisAccount {
+            remember: payload.remember,
+            ..create_auth_redis_account(email, Action::Login, None)
+        },
+        session_info,
+        &WebsitePath::BoilerSwap,
+    )
+    .await
+}
+
+pub async fn remove_passkey(
+    state: Arc<AppState>,
+    email: &str,
+    credential_id: &str,
+) -> Result<(), AppError> {
+    match get_passkey(state.clone(), credential_id).await? {
+        Some((owner, _, _)) if owner == email => {
+            delete_passkey(state.clone(), email, credential_id).await
+        }
+        _ => Err(AppError::BadRequest("Passkey not found".to_string())),
+    }
+}
@@ -1,20 +1,31 @@
 use super::{
-    cookies::generate_cookie,
-    locks::{check_locks, increment_lock_key, is_redis_locked, is_temporarily_locked},
-    models::{Account, Action, RedisAccount, RedisAction, VerifiedTokenResult, WebsitePath},
+    cookies::{generate_cookie, get_cookie, single_cookie},
+    locks::{
+        check_db_lock, check_locks, freeze_account, increment_lock_key, is_redis_locked,
+        is_temporarily_locked,
+    },
+    models::{
+        Account, Action, DeviceSession, LinkAction, RedisAccount, RedisAction, SessionInfo,
+        VerifiedTokenResult, WebsitePath, WebsiteRoute,
+    },
     swap::database::{get_user, insert_user},
+    totp::verify_second_factor,
     twofactor::{generate_code, spawn_code_task},
     utilities::{clear_all_keys, get_key},
-    verify::{hash_password, verify_password},
+    verify::{hash_password, sign_link, verify_link, verify_password},
 };
 use crate::{
     AppError, AppState,
-    api::microservices::redis::{insert_id, remove_id},
+    api::microservices::redis::{insert_id, remove_id, try_get},
+};
+use axum::http::{
+    HeaderValue,
+    header::{HeaderMap, SET_COOKIE},
 };
-use axum::http::header::HeaderMap;
 use chrono::Utc;
 use once_cell::sync::Lazy;
 use redis::{AsyncTypedCommands, Script};
+use sha2::{Digest, Sha256};
 use std::sync::Arc;
 use tokio::task::spawn_blocking;
 use uuid::Uuid;
@@ -23,13 +34,84 @@ static INSERT_SESSION_SCRIPT: Lazy<Script> = Lazy::new(|| {
     Script::new(
         r#"
         redis.call("SETEX", KEYS[1], tonumber(ARGV[3]), ARGV[2])
+        redis.call("SETEX", KEYS[3], tonumber(ARGV[3]), ARGV[6])
         local length = redis.call("LPUSH", KEYS[2], ARGV[1])
-        redis.call("EXPIRE", KEYS[2], tonumber(ARGV[3]))
+        if redis.call("TTL", KEYS[2]) < tonumber(ARGV[3]) then
+            redis.call("EXPIRE", KEYS[2], tonumber(ARGV[3]))
+        end
         if length > tonumber(ARGV[4]) then
             local removed_id = redis.call("RPOP", KEYS[2])
             local removed_key = ARGV[5] .. removed_id
             redis.call("DEL", removed_key)
+            redis.call("DEL", ARGV[7] .. removed_id)
+        end
+    "#,
+    )
+});
+
+static REFRESH_SESSION_SCRIPT: Lazy<Script> = Lazy::new(|| {
+    Script::new(
+        r#"
+        local info = redis.call("GET", KEYS[2])
+        if not info then
+            return 0
+        end
+        local ttl = tonumber(ARGV[1])
+        if cjson.decode(info)["remember"] then
+            ttl = tonumber(ARGV[2])
+        end
+        if redis.call("EXPIRE", KEYS[1], ttl) == 0 then
+            return 0
+        end
+        redis.call("EXPIRE", KEYS[2], ttl)
+        if redis.call("TTL", KEYS[3]) < ttl then
+            redis.call("EXPIRE", KEYS[3], ttl)
+        end
+        return ttl
+    "#,
+    )
+});
+
+static ROTATE_SESSION_SCRIPT: Lazy<Script> = Lazy::new(|| {
+    Script::new(
+        r#"
+        if redis.call("EXISTS", KEYS[1]) == 0 then
+            return 0
+        end
+        redis.call("RENAME", KEYS[1], KEYS[2])
+        if redis.call("EXISTS", KEYS[3]) == 1 then
+            redis.call("RENAME", KEYS[3], KEYS[4])
+        end
+        local position = redis.call("LPOS", KEYS[5], ARGV[1])
+        if position then
+            redis.call("LSET", KEYS[5], position, ARGV[2])
+        end
+        return redis.call("TTL", KEYS[2])
+    "#,
+    )
+});
+
+static MIGRATE_SESSIONS_SCRIPT: Lazy<Script> = Lazy::new(|| {
+    Script::new(
+        r#"
+        if redis.call("EXISTS", KEYS[3]) == 1 then
+            redis.call("RENAME", KEYS[3], KEYS[4])
+        end
+        for i = 4, #ARGV do
+            if redis.call("EXISTS", ARGV[3] .. ARGV[i]) == 1 then
+                redis.call("SET", ARGV[3] .. ARGV[i], ARGV[1], "KEEPTTL")
+            end
         end
+        if redis.call("EXISTS", KEYS[1]) == 0 then
+            return 0
+        end
+        redis.call("RENAME", KEYS[1], KEYS[2])
+        for _, session_id in ipairs(redis.call("LRANGE", KEYS[2], 0, -1)) do
+            if redis.call("EXISTS", ARGV[2] .. session_id) == 1 then
+                redis.call("SET", ARGV[2] .. session_id, ARGV[1], "KEEPTTL")
+            end
+        end
+        return 1
     "#,
     )
 });
@@ -43,10 +125,13 @@ pub async fn create_temporary_session(
     code_key: &Option<String>,
     website_path: &WebsitePath,
 ) -> Result<HeaderMap, AppError> {
+    let id = Uuid::new_v4();
+
     send_code(
         state.clone(),
         redis_action,
         redis_account,
+        &id,
         forgot_key,
         code_key,
         website_path,
@@ -58,7 +143,7 @@ pub async fn create_temporary_session(
         None => &serde_json::to_string(&redis_account)?,
     };
 
-    let id = Uuid::new_v4().to_string();
+    let id = id.to_string();
 
     insert_id(
         state.clone(),
@@ -77,14 +162,28 @@ pub async fn create_temporary_session(
     )
     .await?;
 
+    let ttl_seconds = state
+        .config
+        .session
+        .temporary_session_duration_seconds
+        .into();
+
+    // Email changes and account deletion happen inside a session, so its cookie has to survive
+    if let RedisAction::EmailOld | RedisAction::EmailNew | RedisAction::AccountDelete = redis_action
+    {
+        let mut headers = HeaderMap::new();
+        headers.append(
+            SET_COOKIE,
+            single_cookie(redis_action.as_ref(), &id, ttl_seconds, website_path),
+        );
+
+        return Ok(headers);
+    }
+
     Ok(generate_cookie(
         redis_action.as_ref(),
         &id,
-        state
-            .config
-            .session
-            .temporary_session_duration_seconds
-            .into(),
+        ttl_seconds,
         website_path,
     ))
 }
@@ -93,6 +192,7 @@ async fn send_code(
     state: Arc<AppState>,
     redis_action: &RedisAction,
     redis_account: &RedisAccount,
+    id: &Uuid,
     forgot_key: &Option<String>,
     code_key: &Option<String>,
     website_path: &WebsitePath,
@@ -101,10 +201,37 @@ async fn send_code(
         return Ok(());
     }
 
+    let link_action = match redis_action {
+        RedisAction::Auth => Some(LinkAction::Auth),
+        RedisAction::Forgot => Some(LinkAction::Forgot),
+        _ => None,
+    };
+
+    let link = match link_action {
+        Some(link_action) if *website_path == WebsitePath::BoilerSwap => Some(format!(
+            "{}/{}/{}/login?token={}",
+            state.config.server.svelte_url,
+            website_path.as_ref(),
+            WebsiteRoute::Links.as_ref(),
+            sign_link(
+                &redis_account.email,
+                link_action,
+                id,
+                state
+                    .config
+                    .session
+                    .temporary_session_duration_seconds
+                    .into(),
+            )?
+        )),
+        _ => None,
+    };
+
     spawn_code_task(
         state.clone(),
         redis_account.email.clone(),
         redis_account.code.clone(),
+        link,
         forgot_key.clone(),
         website_path.clone(),
     );
@@ -125,6 +252,7 @@ async fn send_code(
 pub async fn create_session(
     state: Arc<AppState>,
     redis_account: &RedisAccount,
+    session_info: SessionInfo,
     website_path: &WebsitePath,
 ) -> Result<HeaderMap, AppError> {
     if redis_account.action == Action::Signup {
@@ -132,6 +260,7 @@ pub async fn create_session(
     }
 
     let session_id = Uuid::new_v4().to_string();
+    let ttl_seconds = session_duration(state.clone(), redis_account.remember);
 
     insert_session(
         state.clone(),
@@ -140,13 +269,151 @@ pub async fn create_session(
         &session_id,
         RedisAction::SessionStore.as_ref(),
         &redis_account.email,
+        &SessionInfo {
+            remember: redis_account.remember,
+            ..session_info
+        },
     )
     .await?;
 
     Ok(generate_cookie(
         RedisAction::Session.as_ref(),
         &session_id,
-        state.config.session.session_duration_seconds.into(),
+        ttl_seconds,
+        website_path,
+    ))
+}
+
+fn session_duration(state: Arc<AppState>, remember: bool) -> i64 {
+    match remember {
+        true => state
+            .config
+            .session
+            .remember_session_duration_seconds
+            .into(),
+        false => state.config.session.session_duration_seconds.into(),
+    }
+}
+
+// Active sessions slide forward on every authenticated request
+pub async fn refresh_session(
+    state: Arc<AppState>,
+    headers: &HeaderMap,
+    website_path: &WebsitePath,
+) -> Result<Option<HeaderValue>, AppError> {
+    let Some(session_id) = get_cookie(headers, RedisAction::Session.as_ref()) else {
+        return Ok(None);
+    };
+
+    let ttl_seconds: i64 = REFRESH_SESSION_SCRIPT
+        .key(format!(
+            "{}:{}:{}",
+            website_path.as_ref(),
+            RedisAction::Session.as_ref(),
+            session_id
+        ))
+        .key(format!(
+            "{}:{}:{}",
+            website_path.as_ref(),
+            RedisAction::SessionInfo.as_ref(),
+            session_id
+        ))
+        .key(format!(
+            "{}:{}:{}",
+            website_path.as_ref(),
+            RedisAction::SessionStore.as_ref(),
+            get_session_email(state.clone(), website_path, &session_id)
+                .await?
+                .unwrap_or_default()
+        ))
+        .arg(session_duration(state.clone(), false))
+        .arg(session_duration(state.clone(), true))
+        .invoke_async(&mut state.redis_connection_manager.clone())
+        .await?;
+
+    if ttl_seconds <= 0 {
+        return Ok(None);
+    }
+
+    Ok(Some(single_cookie(
+        RedisAction::Session.as_ref(),
+        &session_id,
+        ttl_seconds,
+        website_path,
+    )))
+}
+
+async fn get_session_email(
+    state: Arc<AppState>,
+    website_path: &WebsitePath,
+    session_id: &str,
+) -> Result<Option<String>, AppError> {
+    try_get(
+        state.clone(),
+        &format!(
+            "{}:{}:{}",
+            website_path.as_ref(),
+            RedisAction::Session.as_ref(),
+            session_id
+        ),
+    )
+    .await
+}
+
+// Swaps the session id in place so a stolen cookie stops working after sensitive changes
+pub async fn rotate_session(
+    state: Arc<AppState>,
+    headers: &HeaderMap,
+    website_path: &WebsitePath,
+) -> Result<HeaderMap, AppError> {
+    let session_id = get_cookie(headers, RedisAction::Session.as_ref())
+        .ok_or(AppError::Unauthorized("Unable to verify".to_string()))?;
+    let email = get_session_email(state.clone(), website_path, &session_id)
+        .await?
+        .ok_or(AppError::Unauthorized("Unable to verify".to_string()))?;
+    let new_session_id = Uuid::new_v4().to_string();
+
+    let session_key = |id: &str| {
+        format!(
+            "{}:{}:{}",
+            website_path.as_ref(),
+            RedisAction::Session.as_ref(),
+            id
+        )
+    };
+    let info_key = |id: &str| {
+        format!(
+            "{}:{}:{}",
+            website_path.as_ref(),
+            RedisAction::SessionInfo.as_ref(),
+            id
+        )
+    };
+
+    let ttl_seconds: i64 = ROTATE_SESSION_SCRIPT
+        .key(session_key(&session_id))
+        .key(session_key(&new_session_id))
+        .key(info_key(&session_id))
+        .key(info_key(&new_session_id))
+        .key(format!(
+            "{}:{}:{}",
+            website_path.as_ref(),
+            RedisAction::SessionStore.as_ref(),
+            email
+        ))
+        .arg(&session_id)
+        .arg(&new_session_id)
+        .invoke_async(&mut state.redis_connection_manager.clone())
+        .await?;
+
+    if ttl_seconds <= 0 {
+        return Err(AppError::Unauthorized("Unable to verify".to_string()));
+    }
+
+    Ok(generate_cookie(
+        RedisAction::Session.as_ref(),
+        &new_session_id,
+        ttl_seconds,
         website_path,
     ))
 }
@@ -176,7 +443,10 @@ pub async fn try_create_redis_account(
     )
     .await?;
 
-    Ok(account)
+    Ok(RedisAccount {
+        remember: payload.remember,
+        ..account
+    })
 }
 
 pub async fn get_redis_account(
@@ -231,7 +501,14 @@ pub async fn get_redis_account(
         _ => false,
     };
 
-    if !locked && verified_result.redis_action != RedisAction::Update && code != deserialized.code {
+    // Logins also accept an authenticator or recovery code in place of the emailed one
+    let verified = code == deserialized.code
+        || (!locked
+            && verified_result.redis_action == RedisAction::Auth
+            && deserialized.action == Action::Login
+            && verify_second_factor(state.clone(), &deserialized.email, code).await?);
+
+    if !locked && verified_result.redis_action != RedisAction::Update && !verified {
         increment_lock_key(
             state.clone(),
             website_path.as_ref(),
@@ -358,6 +635,254 @@ pub async fn try_get_redis_account(
     }
 }
 
+pub async fn try_magic_link(
+    state: Arc<AppState>,
+    token: &str,
+    hashed_ip: &str,
+    session_info: SessionInfo,
+) -> Result<(HeaderMap, RedisAction), AppError> {
+    let website_path = WebsitePath::BoilerSwap;
+
+    let (claims, redis_action) = verify_link(token, LinkAction::Auth)
+        .map(|claims| (claims, RedisAction::Auth))
+        .or_else(|| {
+            verify_link(token, LinkAction::Forgot).map(|claims| (claims, RedisAction::Forgot))
+        })
+        .ok_or(AppError::Unauthorized("Unable to verify".to_string()))?;
+
+    let id = claims.id.to_string();
+
+    let serialized_account = try_get(
+        state.clone(),
+        &format!(
+            "{}:{}:{}",
+            website_path.as_ref(),
+            redis_action.as_ref(),
+            &id
+        ),
+    )
+    .await?;
+
+    // The link stands in for the code, so it only works while its temporary session does
+    let code = serialized_account
+        .as_deref()
+        .and_then(|serialized| serde_json::from_str::<RedisAccount>(serialized).ok())
+        .filter(|account| account.email == claims.sub)
+        .map(|account| account.code)
+        .ok_or(AppError::Unauthorized("Unable to verify".to_string()))?;
+
+    let verified_result = VerifiedTokenResult {
+        serialized_account,
+        redis_action,
+        id,
+    };
+
+    let redis_account = try_get_redis_account(
+        state.clone(),
+        &verified_result,
+        &code,
+        hashed_ip,
+        &website_path,
+    )
+    .await?;
+
+    // A reset link ends where the Forgot code does, on the new password step
+    if verified_result.redis_action == RedisAction::Forgot {
+        freeze_account(state.clone(), &redis_account.email, &website_path).await?;
+
+        let headers = create_temporary_session(
+            state.clone(),
+            &verified_result.serialized_account,
+            &redis_account,
+            &RedisAction::Update,
+            &None,
+            &None,
+            &website_path,
+        )
+        .await?;
+
+        return Ok((headers, RedisAction::Update));
+    }
+
+    if check_db_lock(state.clone(), &redis_account.email, &website_path).await? {
+        return Err(AppError::Unauthorized("Unable to verify".to_string()));
+    }
+
+    Ok((
+        create_session(state.clone(), &redis_account, session_info, &website_path).await?,
+        RedisAction::Session,
+    ))
+}
+
+// Moves the session list, every live session, the posting counter and the
+// owner lookups CDC uses on item deletion over to the new email
+pub async fn migrate_sessions(
+    state: Arc<AppState>,
+    website_path: &WebsitePath,
+    email: &str,
+    new_email: &str,
+    item_ids: &[Uuid],
+) -> Result<(), AppError> {
+    let key = |prefix: &str, id: &str| format!("{}:{}:{}", website_path.as_ref(), prefix, id);
+
+    let _: i64 = MIGRATE_SESSIONS_SCRIPT
+        .key(key(RedisAction::SessionStore.as_ref(), email))
+        .key(key(RedisAction::SessionStore.as_ref(), new_email))
+        .key(key(RedisAction::LockedItems.as_ref(), email))
+        .key(key(RedisAction::LockedItems.as_ref(), new_email))
+        .arg(new_email)
+        .arg(key(RedisAction::Session.as_ref(), ""))
+        .arg(key(RedisAction::DeletedItem.as_ref(), ""))
+        .arg(
+            item_ids
+                .iter()
+                .map(|item_id| item_id.to_string())
+                .collect::<Vec<_>>(),
+        )
+        .invoke_async(&mut state.redis_connection_manager.clone())
+        .await?;
+
+    Ok(())
+}
+
+async fn remove_sessions(
+    state: Arc<AppState>,
+    website_path: &WebsitePath,
+    email: &str,
+    session_ids: &[String],
+) -> Result<(), AppError> {
+    let mut pipe = redis::pipe();
+
+    for session_id in session_ids {
+        pipe.lrem(
+            format!(
+                "{}:{}:{}",
+                website_path.as_ref(),
+                RedisAction::SessionStore.as_ref(),
+                email
+            ),
+            0,
+            session_id,
+        )
+        .ignore();
+
+        for key in [RedisAction::Session, RedisAction::SessionInfo] {
+            pipe.del(format!(
+                "{}:{}:{}",
+                website_path.as_ref(),
+                key.as_ref(),
+                session_id
+            ))
+            .ignore();
+        }
+    }
+
+    pipe.query_async::<()>(&mut state.redis_connection_manager.clone())
+        .await?;
+
+    Ok(())
+}
+
+pub async fn delete_other_sessions(
+    state: Arc<AppState>,
+    website_path: &WebsitePath,
+    email: &str,
+    current_session_id: &str,
+) -> Result<(), AppError> {
+    let session_ids: Vec<String> = get_session_ids(state.clone(), website_path, email)
+        .await?
+        .into_iter()
+        .filter(|session_id| session_id != current_session_id)
+        .collect();
+
+    remove_sessions(state.clone(), website_path, email, &session_ids).await
+}
+
+// Session ids are bearer credentials, so devices are only ever exposed by a hash of theirs
+fn session_handle(session_id: &str) -> String {
+    format!("{:x}", Sha256::digest(session_id.as_bytes()))
+}
+
+async fn get_session_ids(
+    state: Arc<AppState>,
+    website_path: &WebsitePath,
+    email: &str,
+) -> Result<Vec<String>, AppError> {
+    Ok(state
+        .redis_connection_manager
+        .clone()
+        .lrange(
+            format!(
+                "{}:{}:{}",
+                website_path.as_ref(),
+                RedisAction::SessionStore.as_ref(),
+                email
+            ),
+            0,
+            -1,
+        )
+        .await?)
+}
+
+pub async fn list_sessions(
+    state: Arc<AppState>,
+    website_path: &WebsitePath,
+    email: &str,
+    current_session_id: &str,
+) -> Result<Vec<DeviceSession>, AppError> {
+    let session_ids = get_session_ids(state.clone(), website_path, email).await?;
+
+    if session_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let session_infos: Vec<Option<String>> = redis::cmd("MGET")
+        .arg(
+            session_ids
+                .iter()
+                .map(|session_id| {
+                    format!(
+                        "{}:{}:{}",
+                        website_path.as_ref(),
+                        RedisAction::SessionInfo.as_ref(),
+                        session_id
+                    )
+                })
+                .collect::<Vec<_>>(),
+        )
+        .query_async(&mut state.redis_connection_manager.clone())
+        .await?;
+
+    Ok(session_ids
+        .iter()
+        .zip(session_infos)
+        .filter_map(|(session_id, session_info)| {
+            Some(DeviceSession {
+                session_id: session_handle(session_id),
+                info: serde_json::from_str(&session_info?).ok()?,
+                current: session_id == current_session_id,
+            })
+        })
+        .collect())
+}
+
+pub async fn revoke_session(
+    state: Arc<AppState>,
+    website_path: &WebsitePath,
+    email: &str,
+    handle: &str,
+) -> Result<(), AppError> {
+    let Some(session_id) = get_session_ids(state.clone(), website_path, email)
+        .await?
+        .into_iter()
+        .find(|session_id| session_handle(session_id) == handle)
+    else {
+        return Err(AppError::BadRequest("Session not found".to_string()));
+    };
+
+    remove_sessions(state.clone(), website_path, email, &[session_id]).await
+}
+
 pub fn create_forgot_redis_account(email: String) -> RedisAccount {
     RedisAccount {
         email,
@@ -365,6 +890,8 @@ pub fn create_forgot_redis_account(email: String) -> RedisAccount {
         code: generate_code().clone(),
         issued_timestamp: None,
         password_hash: None,
+        remember: false,
+        pending_email: None,
     }
 }
 
@@ -379,6 +906,8 @@ pub fn create_auth_redis_account(
         code: generate_code().clone(),
         issued_timestamp: Some(Utc::now().timestamp_millis()),
         password_hash,
+        remember: false,
+        pending_email: None,
     }
 }
 
@@ -403,6 +932,13 @@ pub async fn delete_all_sessions(
     {
         pipe.del(format!("{}:{}:{}", website_path, key, session_id))
             .ignore();
+        pipe.del(format!(
+            "{}:{}:{}",
+            website_path,
+            RedisAction::SessionInfo.as_ref(),
+            session_id
+        ))
+        .ignore();
     }
 
     pipe.del(format!("{}:{}:{}", website_path, key_secondary, email))
@@ -421,15 +957,28 @@ async fn insert_session(
     session_id: &str,
     key_secondary: &str,
     email: &str,
+    session_info: &SessionInfo,
 ) -> Result<(), AppError> {
     let _: () = INSERT_SESSION_SCRIPT
         .key(format!("{}:{}:{}", website_path, key, session_id))
         .key(format!("{}:{}:{}", website_path, key_secondary, email))
+        .key(format!(
+            "{}:{}:{}",
+            website_path,
+            RedisAction::SessionInfo.as_ref(),
+            session_id
+        ))
         .arg(session_id)
         .arg(email)
-        .arg(state.config.session.session_duration_seconds)
+        .arg(session_duration(state.clone(), session_info.remember))
         .arg(state.config.session.max_sessions)
         .arg(format!("{}:{}:", website_path, key))
+        .arg(serde_json::to_string(session_info)?)
+        .arg(format!(
+            "{}:{}:",
+            website_path,
+            RedisAction::SessionInfo.as_ref()
+        ))
         .invoke_async(&mut state.redis_connection_manager.clone())
         .await?;
 
@@ -0,0 +1,230 @@
+use super::{
+    database::{
+        get_admin_item_page, get_item_ttl, get_items_by_ids, get_moderation_queue, get_owned_items,
+        get_user, get_user_role, hide_item, unhide_item, update_role,
+    },
+    models::{AdminItem, AdminItems, AdminItemsQuery, AdminUser, ModerationItem, UserRole},
+};
+use crate::{
+    AppError, AppState, RedisAction, WebsitePath,
+    api::{
+        microservices::redis::try_get,
+        web::{sessions::delete_all_sessions, verify::check_token},
+    },
+};
+use axum::http::header::HeaderMap;
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use scylla::response::PagingState;
+use std::{cmp::Reverse, collections::HashMap, sync::Arc};
+use uuid::Uuid;
+
+pub async fn check_admin(state: Arc<AppState>, headers: HeaderMap) -> Result<String, AppError> {
+    let email = check_token(
+        state.clone(),
+        headers,
+        &[RedisAction::Session],
+        &WebsitePath::BoilerSwap,
+    )
+    .await?
+    .serialized_account
+    .expect("session creation faulty");
+
+    if !is_admin(state.clone(), &email).await? {
+        return Err(AppError::Unauthorized("Unable to verify".to_string()));
+    }
+
+    Ok(email)
+}
+
+// Configured admins need no stored role, which is how the first admin is granted
+async fn is_admin(state: Arc<AppState>, email: &str) -> Result<bool, AppError> {
+    Ok(state.config.website_specific.admin_emails.contains(email)
+        || get_user_role(state.clone(), email, &WebsitePath::BoilerSwap).await?
+            == Some(UserRole::Admin))
+}
+
+pub async fn list_admin_items(
+    state: Arc<AppState>,
+    payload: AdminItemsQuery,
+) -> Result<AdminItems, AppError> {
+    let paging_state = match payload.cursor {
+        Some(cursor) => PagingState::new_from_raw_bytes(
+            URL_SAFE_NO_PAD
+                .decode(cursor)
+                .map_err(|_| AppError::BadRequest("Invalid cursor".to_string()))?,
+        ),
+        None => PagingState::start(),
+    };
+
+    let (rows, next_paging_state) = get_admin_item_page(state.clone(), paging_state).await?;
+
+    let mut details: HashMap<Uuid, (Option<String>, bool)> = rows
+        .into_iter()
+        .map(|(item_id, owner, hidden)| (item_id, (owner, hidden.unwrap_or(false))))
+        .collect();
+
+    let query = payload.query.map(|query| query.to_lowercase());
+
+    // The query narrows the scanned page, so a page can come back short while more remain
+    let items = get_items_by_ids(state.clone(), details.keys().copied().collect(), None)
+        .await?
+        .into_iter()
+        .filter_map(|item| {
+            details
+                .remove(&item.item_id)
+                .map(|(owner, hidden)| AdminItem {
+                    item,
+                    owner,
+                    hidden,
+                })
+        })
+        .filter(|admin_item| {
+            query.as_ref().is_none_or(|query| {
+                admin_item.item.title.to_lowercase().contains(query)
+                    || admin_item.item.description.to_lowercase().contains(query)
+                    || admin_item
+                        .owner
+                        .as_ref()
+                        .is_some_and(|owner| owner.to_lowercase().contains(query))
+            })
+        })
+        .collect();
+
+    Ok(AdminItems {
+        items,
+        cursor: next_paging_state
+            .as_ref()
+            .and_then(PagingState::as_bytes_slice)
+            .map(|bytes| URL_SAFE_NO_PAD.encode(bytes)),
+    })
+}
+
+pub async fn get_moderation_items(state: Arc<AppState>) -> Result<Vec<ModerationItem>, AppError> {
+    let mut queue: HashMap<Uuid, (i64, _)> = get_moderation_queue(state.clone())
+        .await?
+        .into_iter()
+        .map(|(item_id, reports, queued_at)| (item_id, (reports, queued_at)))
+        .collect();
+
+    let mut items: Vec<ModerationItem> =
+        get_items_by_ids(state.clone(), queue.keys().copied().collect(), None)
+            .await?
+            .into_iter()
+            .filter_map(|item| {
+                queue
+                    .remove(&item.item_id)
+                    .map(|(reports, queued_at)| ModerationItem {
+                        item,
+                        reports,
+                        queued_at,
+                    })
+            })
+            .collect();
+
+    items.sort_by_key(|item| Reverse(item.reports));
+
+    Ok(items)
+}
+
+pub async fn try_hide_item(
+    state: Arc<AppState>,
+    item_id: &Uuid,
+    hidden: bool,
+) -> Result<(), AppError> {
+    if !hidden {
+        return unhide_item(state.clone(), item_id).await;
+    }
+
+    if get_item_ttl(state.clone(), item_id).await? <= 0 {
+        return Err(AppError::BadRequest("Item not found".to_string()));
+    }
+
+    hide_item(state.clone(), item_id, 0).await
+}
+
+pub async fn get_admin_user(state: Arc<AppState>, email: &str) -> Result<AdminUser, AppError> {
+    let (_, locked) = get_user(state.clone(), email, &WebsitePath::BoilerSwap)
+        .await?
+        .ok_or(AppError::BadRequest("User not found".to_string()))?;
+
+    let role = if state.config.website_specific.admin_emails.contains(email) {
+        UserRole::Admin
+    } else {
+        get_user_role(state.clone(), email, &WebsitePath::BoilerSwap)
+            .await?
+            .unwrap_or(UserRole::User)
+    };
+
+    let posting_count = try_get(
+        state.clone(),
+        &format!(
+            "{}:{}:{}",
+            WebsitePath::BoilerSwap.as_ref(),
+            RedisAction::LockedItems.as_ref(),
+            email
+        ),
+    )
+    .await?
+    .map(|count| count.parse::<u8>())
+    .transpose()?
+    .unwrap_or(0);
+
+    Ok(AdminUser {
+        email: email.to_string(),
+        role: role.as_ref().to_string(),
+        locked,
+        posting_count,
+        items: get_owned_items(state.clone(), email).await?,
+    })
+}
+
+pub async fn try_ban_user(
+    state: Arc<AppState>,
+    admin_email: &str,
+    email: &str,
+    banned: bool,
+) -> Result<(), AppError> {
+    if admin_email == email {
+        return Err(AppError::BadRequest("Cannot ban yourself".to_string()));
+    }
+
+    let role = get_user_role(state.clone(), email, &WebsitePath::BoilerSwap)
+        .await?
+        .ok_or(AppError::BadRequest("User not found".to_string()))?;
+
+    if is_admin(state.clone(), email).await? {
+        return Err(AppError::BadRequest("Cannot ban an admin".to_string()));
+    }
+
+    // Bans live in the role rather than the locked flag, which the password reset
+    // flow sets and clears on its own and would otherwise lift a ban early.
+    // Banning only ever replaces the User role, so lifting it restores User
+    if !banned && role != UserRole::Banned {
+        return Err(AppError::BadRequest("User is not banned".to_string()));
+    }
+
+    update_role(
+        state.clone(),
+        email,
+        if banned {
+            UserRole::Banned
+        } else {
+            UserRole::User
+        },
+        &WebsitePath::BoilerSwap,
+    )
+    .await?;
+
+    if banned {
+        delete_all_sessions(
+            state.clone(),
+            WebsitePath::BoilerSwap.as_ref(),
+            RedisAction::Session.as_ref(),
+            RedisAction::SessionStore.as_ref(),
+            email,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,234 @@
+use super::{
+    catalog::read_catalog,
+    database::{
+        delete_saved_search, get_alert_searches, get_saved_searches, insert_saved_search,
+        saved_search_ttl,
+    },
+    models::{CatalogKind, Item, SavedSearch, SavedSearchPayload, SavedSearchTtlRow},
+    utilities::validate_item_attribute,
+};
+use crate::{
+    AppError, AppState, RedisAction, WebsitePath,
+    api::{
+        microservices::database::schema::columns::boiler_swap::saved_searches_by_type,
+        web::{
+            locks::is_temporarily_locked,
+            models::{LinkAction, WebsiteRoute},
+            twofactor::send_email,
+            verify::{sign_link, verify_link},
+        },
+    },
+};
+use scylla::{response::PagingState, statement::batch::Batch};
+use std::{collections::HashSet, ops::ControlFlow, sync::Arc};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+pub async fn try_save_search(
+    state: Arc<AppState>,
+    email: &str,
+    payload: SavedSearchPayload,
+) -> Result<Uuid, AppError> {
+    if !payload.query.is_empty() {
+        validate_item_attribute(&payload.query).map_err(|e| AppError::BadRequest(e.to_string()))?;
+    }
+
+    if get_saved_searches(state.clone(), email).await?.len()
+        >= state.config.website_specific.max_saved_searches.into()
+    {
+        return Err(AppError::BadRequest("Too many saved searches".to_string()));
+    }
+
+    insert_saved_search(state.clone(), email, payload).await
+}
+
+pub async fn unsubscribe(state: Arc<AppState>, token: &str) -> Result<(), AppError> {
+    let claims = verify_link(token, LinkAction::Unsubscribe)
+        .ok_or(AppError::Unauthorized("Unable to verify".to_string()))?;
+
+    delete_saved_search(state.clone(), &claims.sub, &claims.id).await
+}
+
+pub fn spawn_alert_task(state: Arc<AppState>, item: Item, owner: Option<String>) {
+    tokio::spawn(async move {
+        if let Err(e) = send_alerts(state.clone(), &item, owner.as_deref()).await {
+            warn!("Sending alerts failed: {}", e);
+        }
+    });
+}
+
+async fn send_alerts(
+    state: Arc<AppState>,
+    item: &Item,
+    owner: Option<&str>,
+) -> Result<(), AppError> {
+    let item_type = read_catalog(&state.catalog)
+        .code(CatalogKind::ItemType, &item.item_type)
+        .ok();
+    let mut alerted: HashSet<String> = HashSet::new();
+
+    for partition in item_type
+        .into_iter()
+        .chain([saved_searches_by_type::ANY_ITEM_TYPE])
+    {
+        for (email, search) in get_alert_searches(state.clone(), &item.campus, partition).await? {
+            if owner == Some(email.as_str()) || alerted.contains(&email) || !is_match(&search, item)
+            {
+                continue;
+            }
+
+            send_alert(state.clone(), &email, &search, item).await?;
+
+            alerted.insert(email);
+        }
+    }
+
+    Ok(())
+}
+
+// Searches saved before the alert index existed are copied over once, keeping their remaining TTL
+pub async fn backfill_alert_index(state: Arc<AppState>) -> Result<(), AppError> {
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.peek_alert_searches,
+            &[],
+            PagingState::start(),
+        )
+        .await?;
+
+    if returned_rows.into_rows_result()?.rows_num() > 0 {
+        return Ok(());
+    }
+
+    let mut paging_state = PagingState::start();
+    let mut indexed = 0;
+
+    loop {
+        let (query_result, paging_state_response) = state
+            .database_session
+            .execute_single_page(
+                &state.database_queries.boiler_swap.get_all_saved_searches,
+                &[],
+                paging_state,
+            )
+            .await?;
+
+        let row_result = query_result.into_rows_result()?;
+
+        let mut batch: Batch = Default::default();
+        let mut batch_values = Vec::new();
+
+        for row in row_result.rows::<SavedSearchTtlRow>()? {
+            let (email, search_id, query, item_type, condition, location, ttl) = row?;
+
+            // Searches from campuses that were dropped from the config have nowhere to go
+            let Ok(campus) = state.config.website_specific.campuses.for_email(email) else {
+                continue;
+            };
+
+            batch.append_statement(
+                state
+                    .database_queries
+                    .boiler_swap
+                    .insert_alert_search
+                    .clone(),
+            );
+            batch_values.push((
+                campus.id.clone(),
+                item_type.unwrap_or(saved_searches_by_type::ANY_ITEM_TYPE),
+                email,
+                search_id,
+                query,
+                condition,
+                location,
+                ttl.unwrap_or_else(saved_search_ttl),
+            ));
+        }
+
+        if !batch_values.is_empty() {
+            state.database_session.batch(&batch, &batch_values).await?;
+            indexed += batch_values.len();
+        }
+
+        match paging_state_response.into_paging_control_flow() {
+            ControlFlow::Break(()) => {
+                break;
+            }
+            ControlFlow::Continue(new_paging_state) => paging_state = new_paging_state,
+        }
+    }
+
+    info!("Backfilled {} saved searches into the alert index", indexed);
+
+    Ok(())
+}
+
+fn is_match(search: &SavedSearch, item: &Item) -> bool {
+    let text = format!("{} {}", item.title, item.description).to_lowercase();
+
+    search
+        .item_type
+        .as_ref()
+        .is_none_or(|item_type| *item_type == item.item_type)
+        && search
+            .condition
+            .as_ref()
+            .is_none_or(|condition| *condition == item.condition)
+        && search
+            .location
+            .as_ref()
+            .is_none_or(|location| *location == item.location)
+        && search
+            .query
+            .to_lowercase()
+            .split_whitespace()
+            .all(|word| text.contains(word))
+}
+
+async fn send_alert(
+    state: Arc<AppState>,
+    email: &str,
+    search: &SavedSearch,
+    item: &Item,
+) -> Result<(), AppError> {
+    if is_temporarily_locked(
+        state.clone(),
+        WebsitePath::BoilerSwap.as_ref(),
+        RedisAction::LockedAlerts.as_ref(),
+        email,
+        state.config.website_specific.alert_cooldown_seconds.into(),
+    )
+    .await?
+    {
+        return Ok(());
+    }
+
+    let unsubscribe_link = format!(
+        "{}/{}/{}/unsubscribe?token={}",
+        state.config.server.svelte_url,
+        WebsitePath::BoilerSwap.as_ref(),
+        WebsiteRoute::Links.as_ref(),
+        sign_link(email, LinkAction::Unsubscribe, &search.search_id, 7_776_000,)?
+    );
+
+    if let Err(e) = send_email(
+        state.clone(),
+        email,
+        "Alert",
+        format!(
+            "A new item matches your saved search \"{}\": {}\n\nBrowse it at {}/{}/browse\n\nUnsubscribe: {}",
+            search.query,
+            item.title,
+            state.config.server.svelte_url,
+            WebsitePath::BoilerSwap.as_ref(),
+            unsubscribe_link
+        ),
+    )
+    .await
+    {
+        warn!("Alert email failed: {}", e);
+    }
+
+    Ok(())
+}
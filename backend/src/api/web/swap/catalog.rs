@@ -0,0 +1,395 @@
+use super::{
+    database::was_applied,
+    models::{CatalogEntry, CatalogKind, CatalogPayload, CatalogRow, Geo, ItemCodes, ItemPayload},
+    utilities::validate_item_attribute,
+};
+use crate::{AppError, AppState, api::microservices::database::init::DatabaseQueries};
+use scylla::{
+    client::session::Session, response::PagingState, statement::batch::Batch, value::Row,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock, RwLockReadGuard},
+};
+
+const DEFAULT_CATALOG: &[(CatalogKind, &[(&str, &str)])] = &[
+    (
+        CatalogKind::ItemType,
+        &[
+            ("Furniture", "Furniture"),
+            ("Electronics", "Electronics"),
+            ("Books", "Books"),
+            ("Kitchen", "Kitchen"),
+            ("Clothing", "Clothing"),
+            ("Other", "Other"),
+            ("Decor", "Decor"),
+        ],
+    ),
+    (
+        CatalogKind::Condition,
+        &[
+            ("Excellent", "Excellent - Like new, minimal wear"),
+            ("Good", "Good - Some wear but fully functional"),
+            ("Fair", "Fair - Noticeable wear but still usable"),
+        ],
+    ),
+    (
+        CatalogKind::Location,
+        &[
+            ("CaryQuadEast", "Cary Quad - East"),
+            ("WileyHall", "Wiley Hall"),
+            ("HarrisonHall", "Harrison Hall"),
+            ("EarhartHall", "Earhart Hall"),
+            ("HillenbrandHall", "Hillenbrand Hall"),
+            ("ThirdStreetSuites", "Third Street Suites"),
+        ],
+    ),
+    (
+        CatalogKind::Emoji,
+        &[
+            ("Chair", "🪑"),
+            ("Snowflake", "❄️"),
+            ("Books", "📚"),
+            ("Pan", "🍳"),
+            ("Monitor", "🖥️"),
+            ("Decor", "🎨"),
+        ],
+    ),
+];
+
+const MAX_CATALOG_CLAIMS: u8 = 3;
+
+const DEFAULT_COORDINATES: &[(&str, Geo)] = &[
+    (
+        "CaryQuadEast",
+        Geo {
+            lat: 40.4275,
+            lng: -86.9178,
+        },
+    ),
+    (
+        "WileyHall",
+        Geo {
+            lat: 40.4262,
+            lng: -86.9207,
+        },
+    ),
+    (
+        "HarrisonHall",
+        Geo {
+            lat: 40.4243,
+            lng: -86.9254,
+        },
+    ),
+    (
+        "EarhartHall",
+        Geo {
+            lat: 40.4256,
+            lng: -86.9248,
+        },
+    ),
+    (
+        "HillenbrandHall",
+        Geo {
+            lat: 40.4268,
+            lng: -86.9265,
+        },
+    ),
+    (
+        "ThirdStreetSuites",
+        Geo {
+            lat: 40.4221,
+            lng: -86.9208,
+        },
+    ),
+];
+
+const UNKNOWN_ENTRY: &str = "Unknown";
+
+// Rows written before campuses existed, and the seeded halls, all belong to Purdue
+pub const LEGACY_CAMPUS: &str = "purdue";
+
+#[derive(Default)]
+pub struct Catalog {
+    entries: HashMap<CatalogKind, Vec<CatalogEntry>>,
+}
+
+impl Catalog {
+    pub fn name(&self, kind: CatalogKind, code: &i8) -> String {
+        self.try_name(kind, code)
+            .unwrap_or_else(|| UNKNOWN_ENTRY.to_string())
+    }
+
+    pub fn try_name(&self, kind: CatalogKind, code: &i8) -> Option<String> {
+        self.entries
+            .get(&kind)?
+            .iter()
+            .find(|entry| entry.code == *code)
+            .map(|entry| entry.name.clone())
+    }
+
+    pub fn code(&self, kind: CatalogKind, name: &str) -> Result<i8, &'static str> {
+        self.entries
+            .get(&kind)
+            .and_then(|entries| entries.iter().find(|entry| entry.name == name))
+            .map(|entry| entry.code)
+            .ok_or(match kind {
+                CatalogKind::ItemType => "Invalid item type",
+                CatalogKind::Condition => "Invalid condition",
+                CatalogKind::Location => "Invalid location",
+                CatalogKind::Emoji => "Invalid emoji",
+            })
+    }
+
+    pub fn geo(&self, location_code: &i8) -> Option<Geo> {
+        self.entries
+            .get(&CatalogKind::Location)?
+            .iter()
+            .find(|entry| entry.code == *location_code)?
+            .geo
+    }
+
+    pub fn geo_by_name(&self, location: &str) -> Option<Geo> {
+        self.entries
+            .get(&CatalogKind::Location)?
+            .iter()
+            .find(|entry| entry.name == location)?
+            .geo
+    }
+
+    pub fn item_codes(&self, item: &ItemPayload, campus: &str) -> Result<ItemCodes, &'static str> {
+        let location = self.code(CatalogKind::Location, &item.location)?;
+
+        if !self.serves(&location, campus) {
+            return Err("Invalid location");
+        }
+
+        Ok(ItemCodes {
+            item_type: self.code(CatalogKind::ItemType, &item.item_type)?,
+            condition: self.code(CatalogKind::Condition, &item.condition)?,
+            location,
+            emoji: self.code(CatalogKind::Emoji, &item.emoji)?,
+        })
+    }
+
+    pub fn entries(&self, campus: Option<&str>) -> HashMap<String, Vec<CatalogEntry>> {
+        self.entries
+            .iter()
+            .map(|(kind, entries)| {
+                (
+                    kind.as_ref().to_string(),
+                    entries
+                        .iter()
+                        .filter(|entry| campus.is_none_or(|campus| entry.is_shared_with(campus)))
+                        .cloned()
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    fn serves(&self, location_code: &i8, campus: &str) -> bool {
+        self.entries
+            .get(&CatalogKind::Location)
+            .and_then(|entries| entries.iter().find(|entry| entry.code == *location_code))
+            .is_some_and(|entry| entry.is_shared_with(campus))
+    }
+
+    fn insert(&mut self, kind: CatalogKind, entry: CatalogEntry) {
+        self.entries.entry(kind).or_default().push(entry);
+    }
+}
+
+impl CatalogEntry {
+    fn is_shared_with(&self, campus: &str) -> bool {
+        self.campus
+            .as_deref()
+            .is_none_or(|entry_campus| entry_campus == campus)
+    }
+}
+
+pub fn read_catalog(catalog: &RwLock<Catalog>) -> RwLockReadGuard<'_, Catalog> {
+    catalog.read().expect("catalog lock poisoned")
+}
+
+pub async fn load_catalog(
+    database_session: &Session,
+    database_queries: &DatabaseQueries,
+) -> Result<Catalog, AppError> {
+    let (returned_rows, _) = database_session
+        .execute_single_page(
+            &database_queries.boiler_swap.get_catalog,
+            &[],
+            PagingState::start(),
+        )
+        .await?;
+
+    let row_vec: Vec<CatalogRow> = returned_rows
+        .into_rows_result()?
+        .rows::<CatalogRow>()?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if row_vec.is_empty() {
+        return seed_catalog(database_session, database_queries).await;
+    }
+
+    let mut catalog = Catalog::default();
+
+    for (kind_i8, code, name, label, latitude, longitude, campus) in row_vec {
+        if let Ok(kind) = CatalogKind::try_from(kind_i8 as u8) {
+            catalog.insert(
+                kind,
+                CatalogEntry {
+                    code,
+                    name,
+                    label,
+                    geo: latitude.zip(longitude).map(|(lat, lng)| Geo { lat, lng }),
+                    campus,
+                },
+            );
+        }
+    }
+
+    Ok(catalog)
+}
+
+async fn seed_catalog(
+    database_session: &Session,
+    database_queries: &DatabaseQueries,
+) -> Result<Catalog, AppError> {
+    let mut catalog = Catalog::default();
+    let mut batch: Batch = Default::default();
+    let mut batch_values = Vec::new();
+
+    for (kind, entries) in DEFAULT_CATALOG {
+        for (code, (name, label)) in entries.iter().enumerate() {
+            let geo = DEFAULT_COORDINATES
+                .iter()
+                .find(|(location, _)| *kind == CatalogKind::Location && location == name)
+                .map(|(_, geo)| *geo);
+            let campus = (*kind == CatalogKind::Location).then_some(LEGACY_CAMPUS);
+
+            batch.append_statement(database_queries.boiler_swap.insert_catalog_entry.clone());
+            batch_values.push((
+                *kind as i8,
+                code as i8,
+                *name,
+                *label,
+                geo.map(|geo| geo.lat),
+                geo.map(|geo| geo.lng),
+                campus,
+            ));
+
+            catalog.insert(
+                *kind,
+                CatalogEntry {
+                    code: code as i8,
+                    name: name.to_string(),
+                    label: label.to_string(),
+                    geo,
+                    campus: campus.map(str::to_string),
+                },
+            );
+        }
+    }
+
+    database_session.batch(&batch, &batch_values).await?;
+
+    Ok(catalog)
+}
+
+pub async fn try_add_catalog_entry(
+    state: Arc<AppState>,
+    payload: CatalogPayload,
+) -> Result<(), AppError> {
+    if payload.name.is_empty()
+        || payload.name.len() > 32
+        || !payload.name.chars().all(|c| c.is_ascii_alphanumeric())
+    {
+        return Err(AppError::BadRequest("Invalid name".to_string()));
+    }
+
+    validate_item_attribute(&payload.label).map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+    if let Some(geo) = payload.geo
+        && (!(-90.0..=90.0).contains(&geo.lat) || !(-180.0..=180.0).contains(&geo.lng))
+    {
+        return Err(AppError::BadRequest("Invalid coordinates".to_string()));
+    }
+
+    if let Some(campus) = &payload.campus
+        && state.config.website_specific.campuses.get(campus).is_none()
+    {
+        return Err(AppError::BadRequest("Invalid campus".to_string()));
+    }
+
+    // The code is claimed with a conditional insert, so a concurrent add on this or
+    // another instance makes us reload the catalog and pick the next free code
+    let mut attempts = 0;
+
+    let code = loop {
+        let code = {
+            let catalog = read_catalog(&state.catalog);
+
+            if catalog.code(payload.kind, &payload.name).is_ok() {
+                return Err(AppError::BadRequest("Entry already exists".to_string()));
+            }
+
+            catalog
+                .entries
+                .get(&payload.kind)
+                .and_then(|entries| entries.iter().map(|entry| entry.code).max())
+                .map_or(Some(0), |code| code.checked_add(1))
+                .ok_or(AppError::BadRequest("Catalog is full".to_string()))?
+        };
+
+        let (returned_rows, _) = state
+            .database_session
+            .execute_single_page(
+                &state.database_queries.boiler_swap.claim_catalog_entry,
+                (
+                    payload.kind as i8,
+                    code,
+                    &payload.name,
+                    &payload.label,
+                    payload.geo.map(|geo| geo.lat),
+                    payload.geo.map(|geo| geo.lng),
+                    &payload.campus,
+                ),
+                PagingState::start(),
+            )
+            .await?;
+
+        if was_applied(returned_rows.into_rows_result()?.first_row::<Row>()?) {
+            break code;
+        }
+
+        attempts += 1;
+
+        if attempts == MAX_CATALOG_CLAIMS {
+            return Err(AppError::BadRequest(
+                "Catalog is busy, try again".to_string(),
+            ));
+        }
+
+        let catalog = load_catalog(&state.database_session, &state.database_queries).await?;
+        *state.catalog.write().expect("catalog lock poisoned") = catalog;
+    };
+
+    state
+        .catalog
+        .write()
+        .expect("catalog lock poisoned")
+        .insert(
+            payload.kind,
+            CatalogEntry {
+                code,
+                name: payload.name,
+                label: payload.label,
+                geo: payload.geo,
+                campus: payload.campus,
+            },
+        );
+
+    Ok(())
+}
@@ -1,27 +1,37 @@
 use super::{
-    models::{Condition, CronItem, CronItemRow, Emoji, Item, ItemType, Location},
+    catalog::{Catalog, LEGACY_CAMPUS},
+    database::{delete_item_photos, delete_owned_item, delete_reports, expiration_ttl},
+    favorites::prune_favorites,
+    models::{CatalogKind, Item, ItemStatus},
     redis::decrement_items,
 };
+use crate::metrics::{RedisMetricAction, set_redis_metric};
 use crate::{
-    AppError, AppState, RedisAction,
+    AppError, AppState, RedisAction, WebsitePath,
     api::microservices::{
-        cdc::{get_cdc_date, get_cdc_id, get_cdc_text, get_cdc_u8},
+        cdc::{
+            get_cdc_date, get_cdc_id, get_cdc_text, get_cdc_tinyint, get_cdc_uuid_list,
+            try_get_cdc_text, try_get_cdc_u8,
+        },
         database::{init::DatabaseQueries, schema::columns::boiler_swap::items},
         meilisearch::delete_item,
         redis::{remove_id, try_get},
     },
 };
 use anyhow::Result as anyResult;
-use chrono::Utc;
+use chrono::{Duration as chronoDuration, NaiveDate, Utc};
+use redis::aio::ConnectionManager;
 use scylla::{client::session::Session, response::PagingState, statement::batch::Batch};
 use scylla_cdc::consumer::CDCRow;
-use std::{ops::ControlFlow, sync::Arc};
+use std::{ops::ControlFlow, sync::Arc, time::Instant};
 use tokio_cron_scheduler::{Job, JobScheduler};
-use tracing::warn;
+use tracing::{info, warn};
+use uuid::Uuid;
 
 pub async fn spawn_ttl_task(
     database_session: Arc<Session>,
     database_queries: &DatabaseQueries,
+    redis_connection_manager: ConnectionManager,
 ) -> Result<(), AppError> {
     let scheduler = JobScheduler::new().await?;
 
@@ -32,11 +42,16 @@ pub async fn spawn_ttl_task(
         .add(Job::new_async("1 0 0 * * *", move |_uuid, _lock| {
             let database_session = database_session.clone();
             let database_queries = database_queries.clone();
+            let redis_connection_manager = redis_connection_manager.clone();
 
             Box::pin(async move {
-                if expire_ttl(database_session, &database_queries)
-                    .await
-                    .is_err()
+                if expire_ttl(
+                    database_session,
+                    &database_queries,
+                    redis_connection_manager,
+                )
+                .await
+                .is_err()
                 {
                     warn!("Expiring ttl failed!");
                 }
@@ -51,51 +66,189 @@ pub async fn spawn_ttl_task(
     Ok(())
 }
 
-pub fn convert_cron_items(row_vec: &[CronItemRow]) -> Vec<CronItem> {
-    row_vec
-        .iter()
-        .map(|(id, expiration_date)| CronItem {
-            item_id: *id,
-            expiration_date: *expiration_date,
-        })
-        .collect()
+// Listings posted before the expiration index existed are only swept once they are in it
+pub async fn backfill_expiration_index(
+    database_session: Arc<Session>,
+    database_queries: &DatabaseQueries,
+) -> Result<(), AppError> {
+    let (returned_rows, _) = database_session
+        .execute_single_page(
+            &database_queries.boiler_swap.peek_expiration_index,
+            &[],
+            PagingState::start(),
+        )
+        .await?;
+
+    if returned_rows.into_rows_result()?.rows_num() > 0 {
+        return Ok(());
+    }
+
+    let today = Utc::now().date_naive();
+    let mut paging_state = PagingState::start();
+    let mut indexed = 0;
+
+    loop {
+        let (query_result, paging_state_response) = database_session
+            .execute_single_page(
+                &database_queries.boiler_swap.get_all_expiration_dates,
+                &[],
+                paging_state,
+            )
+            .await?;
+
+        let mut expired_batch: Batch = Default::default();
+        let mut expired_values = Vec::new();
+        let mut index_batch: Batch = Default::default();
+        let mut index_values = Vec::new();
+
+        for row in query_result
+            .into_rows_result()?
+            .rows::<(Uuid, NaiveDate)>()?
+        {
+            let (item_id, expiration_date) = row?;
+
+            if expiration_date < today {
+                expired_batch.append_statement(database_queries.boiler_swap.delete_item.clone());
+                expired_values.push((item_id,));
+            } else {
+                index_batch
+                    .append_statement(database_queries.boiler_swap.insert_expiring_item.clone());
+                index_values.push((expiration_date, item_id, expiration_ttl(expiration_date)));
+            }
+        }
+
+        if !expired_values.is_empty() {
+            database_session
+                .batch(&expired_batch, &expired_values)
+                .await?;
+        }
+
+        if !index_values.is_empty() {
+            database_session.batch(&index_batch, &index_values).await?;
+            indexed += index_values.len();
+        }
+
+        match paging_state_response.into_paging_control_flow() {
+            ControlFlow::Break(()) => {
+                break;
+            }
+            ControlFlow::Continue(new_paging_state) => paging_state = new_paging_state,
+        }
+    }
+
+    info!("Backfilled {} items into the expiration index", indexed);
+
+    Ok(())
 }
 
+// Buckets outlive their expiration date by two days (see expiration_ttl), so sweeping
+// the last two keeps a restart around midnight from skipping a day
 pub async fn expire_ttl(
     database_session: Arc<Session>,
     database_queries: &DatabaseQueries,
+    redis_connection_manager: ConnectionManager,
 ) -> Result<(), AppError> {
-    let mut paging_state = PagingState::start();
+    let started = Instant::now();
+    let today = Utc::now().date_naive();
+    let mut expired = 0;
+
+    for days_ago in (1..=2).rev() {
+        expired += expire_bucket(
+            database_session.clone(),
+            database_queries,
+            today - chronoDuration::days(days_ago),
+            today,
+        )
+        .await?;
+    }
 
-    let mut batch: Batch = Default::default();
-    let mut batch_values = Vec::new();
+    let elapsed = started.elapsed().as_millis() as usize;
+
+    info!("Expired {} items in {}ms", expired, elapsed);
+
+    set_redis_metric(
+        redis_connection_manager.clone(),
+        &format!(
+            "{}:{}:{}",
+            WebsitePath::BoilerSwap.as_ref(),
+            RedisAction::Metric.as_ref(),
+            RedisMetricAction::ExpiredItems.as_ref()
+        ),
+        &expired,
+    )
+    .await?;
+
+    set_redis_metric(
+        redis_connection_manager,
+        &format!(
+            "{}:{}:{}",
+            WebsitePath::BoilerSwap.as_ref(),
+            RedisAction::Metric.as_ref(),
+            RedisMetricAction::ExpiryMillis.as_ref()
+        ),
+        &elapsed,
+    )
+    .await?;
+
+    Ok(())
+}
+
+async fn expire_bucket(
+    database_session: Arc<Session>,
+    database_queries: &DatabaseQueries,
+    bucket: NaiveDate,
+    today: NaiveDate,
+) -> Result<usize, AppError> {
+    let mut paging_state = PagingState::start();
+    let mut expired = 0;
 
     loop {
         let (query_result, paging_state_response) = database_session
             .execute_single_page(
-                &database_queries.boiler_swap.get_cron_items,
-                &[],
+                &database_queries.boiler_swap.get_expiring_item_ids,
+                (bucket,),
                 paging_state,
             )
             .await?;
 
-        let row_result = query_result.into_rows_result()?;
-
-        let row_vec: Vec<CronItemRow> = row_result
-            .rows::<CronItemRow>()?
+        let ids: Vec<Uuid> = query_result
+            .into_rows_result()?
+            .rows::<(Uuid,)>()?
+            .map(|row| row.map(|(id,)| id))
             .collect::<Result<Vec<_>, _>>()?;
 
-        let items: Vec<CronItem> = convert_cron_items(&row_vec);
+        if ids.is_empty() {
+            break;
+        }
 
-        for item in items {
-            if item.expiration_date < Utc::now().date_naive() {
-                batch.append_statement(database_queries.boiler_swap.delete_item.clone());
+        // Renewed or already deleted items leave stale entries behind in older buckets
+        let (returned_rows, _) = database_session
+            .execute_single_page(
+                &database_queries.boiler_swap.get_expiration_dates,
+                (ids,),
+                PagingState::start(),
+            )
+            .await?;
+
+        let mut batch: Batch = Default::default();
+        let mut batch_values = Vec::new();
 
-                batch_values.push((item.item_id,));
+        for row in returned_rows
+            .into_rows_result()?
+            .rows::<(Uuid, NaiveDate)>()?
+        {
+            let (item_id, expiration_date) = row?;
+
+            if expiration_date < today {
+                batch.append_statement(database_queries.boiler_swap.delete_item.clone());
+                batch_values.push((item_id,));
             }
         }
 
-        database_session.batch(&batch, &batch_values).await?;
+        if !batch_values.is_empty() {
+            database_session.batch(&batch, &batch_values).await?;
+            expired += batch_values.len();
+        }
 
         match paging_state_response.into_paging_control_flow() {
             ControlFlow::Break(()) => {
@@ -105,31 +258,44 @@ pub async fn expire_ttl(
         }
     }
 
-    Ok(())
+    database_session
+        .execute_single_page(
+            &database_queries.boiler_swap.delete_expiration_bucket,
+            (bucket,),
+            PagingState::start(),
+        )
+        .await?;
+
+    Ok(expired)
 }
 
-pub fn convert_cdc_item(data: &CDCRow<'_>) -> Item {
+pub fn convert_cdc_item(data: &CDCRow<'_>, catalog: &Catalog) -> Item {
     Item {
         item_id: get_cdc_id(data, items::ITEM_ID),
-        item_type: ItemType::try_from(get_cdc_u8(data, items::ITEM_TYPE))
-            .unwrap_or(ItemType::Other)
-            .as_ref()
-            .to_string(),
+        item_type: catalog.name(
+            CatalogKind::ItemType,
+            &get_cdc_tinyint(data, items::ITEM_TYPE),
+        ),
         title: get_cdc_text(data, items::TITLE),
-        condition: Condition::try_from(get_cdc_u8(data, items::CONDITION))
-            .unwrap_or(Condition::Fair)
-            .as_ref()
-            .to_string(),
-        location: Location::try_from(get_cdc_u8(data, items::LOCATION))
-            .unwrap_or(Location::CaryQuadEast)
-            .as_ref()
-            .to_string(),
+        condition: catalog.name(
+            CatalogKind::Condition,
+            &get_cdc_tinyint(data, items::CONDITION),
+        ),
+        location: catalog.name(
+            CatalogKind::Location,
+            &get_cdc_tinyint(data, items::LOCATION),
+        ),
         description: get_cdc_text(data, items::DESCRIPTION),
-        emoji: Emoji::try_from(get_cdc_u8(data, items::EMOJI))
-            .unwrap_or(Emoji::Books)
+        emoji: catalog.name(CatalogKind::Emoji, &get_cdc_tinyint(data, items::EMOJI)),
+        expiration_date: get_cdc_date(data, items::EXPIRATION_DATE),
+        photos: get_cdc_uuid_list(data, items::PHOTOS),
+        status: try_get_cdc_u8(data, items::STATUS)
+            .and_then(|status| ItemStatus::try_from(status).ok())
+            .unwrap_or(ItemStatus::Available)
             .as_ref()
             .to_string(),
-        expiration_date: get_cdc_date(data, items::EXPIRATION_DATE),
+        campus: try_get_cdc_text(data, items::CAMPUS).unwrap_or_else(|| LEGACY_CAMPUS.to_string()),
+        geo: catalog.geo(&get_cdc_tinyint(data, items::LOCATION)),
     }
 }
 
@@ -145,23 +311,34 @@ pub async fn handle_item_deletion(
 
     delete_item(state.meili_client.clone(), meili_index, id).await?;
 
-    decrement_items(
-        state.redis_connection_manager.clone(),
-        website_path,
-        RedisAction::LockedItems.as_ref(),
-        &try_get(
-            state.clone(),
-            &format!(
-                "{}:{}:{}",
-                website_path,
-                redis_deletion_name,
-                &id.to_string()
-            ),
-        )
-        .await?
-        .expect("item insertion misconfigured"),
+    // Account deletion purges the mapping after clearing the owner side itself
+    if let Some(email) = try_get(
+        state.clone(),
+        &format!(
+            "{}:{}:{}",
+            website_path,
+            redis_deletion_name,
+            &id.to_string()
+        ),
     )
-    .await?;
+    .await?
+    {
+        decrement_items(
+            state.redis_connection_manager.clone(),
+            website_path,
+            RedisAction::LockedItems.as_ref(),
+            &email,
+        )
+        .await?;
+
+        delete_owned_item(state.clone(), &email, &id).await?;
+    }
+
+    delete_item_photos(state.clone(), &id).await?;
+
+    delete_reports(state.clone(), &id).await?;
+
+    prune_favorites(state.clone(), &id).await?;
 
     remove_id(
         state.clone(),
@@ -1,43 +1,309 @@
-use super::models::{Condition, Emoji, Item, ItemPayload, ItemRow, ItemType, Location};
+use super::{
+    catalog::{Catalog, LEGACY_CAMPUS, read_catalog},
+    models::{
+        AdminItemRow, CatalogKind, FavoriteRow, Item, ItemPayload, ItemRenewal, ItemRow,
+        ItemStatus, Message, MessageRow, ModerationRow, RenewalRow, ReportReason, SavedSearch,
+        SavedSearchPayload, SavedSearchRow, Thread, ThreadMessageRow, ThreadRole, ThreadRow,
+        UserFavoriteRow, UserRole, UserSavedSearchRow, UserThreadRow,
+    },
+};
+
 use crate::{
     AppError, AppState,
     api::{
-        microservices::cdc::convert_i8_to_u8,
-        web::models::{RedisAccount, WebsitePath},
+        microservices::{
+            cdc::convert_i8_to_u8,
+            database::schema::columns::boiler_swap::{saved_searches, saved_searches_by_type},
+        },
+        web::models::{Passkey, PasskeyRow, RedisAccount, UserRow, WebsitePath},
     },
 };
-use chrono::{Duration as chronoDuration, Utc};
-use scylla::response::{PagingState, query_result::FirstRowError::RowsEmpty};
-use std::sync::Arc;
+use chrono::{DateTime, Duration as chronoDuration, NaiveDate, Utc};
+use scylla::{
+    response::{PagingState, query_result::FirstRowError::RowsEmpty},
+    serialize::row::SerializeRow,
+    statement::{batch::Batch, prepared::PreparedStatement},
+    value::{CqlValue, Row},
+};
+use std::{ops::ControlFlow, sync::Arc};
 use uuid::Uuid;
 
-pub async fn insert_item(state: Arc<AppState>, item: ItemPayload) -> Result<Uuid, AppError> {
-    let fallback_page_state = PagingState::start();
+pub async fn insert_item(
+    state: Arc<AppState>,
+    item: ItemPayload,
+    email: &str,
+) -> Result<Uuid, AppError> {
+    let campus = &state.config.website_specific.campuses.for_email(email)?.id;
+    let codes = read_catalog(&state.catalog)
+        .item_codes(&item, campus)
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
     let id = Uuid::new_v4();
+    let expiration_date = Utc::now().date_naive()
+        + chronoDuration::days(
+            state
+                .config
+                .website_specific
+                .expiration_days
+                .days(&item.item_type) as i64,
+        );
+    let ttl = expiration_ttl(expiration_date);
+
+    let mut batch: Batch = Default::default();
+    batch.append_statement(state.database_queries.boiler_swap.insert_item.clone());
+    batch.append_statement(state.database_queries.boiler_swap.insert_owned_item.clone());
+    batch.append_statement(
+        state
+            .database_queries
+            .boiler_swap
+            .insert_expiring_item
+            .clone(),
+    );
 
     state
         .database_session
-        .execute_single_page(
-            &state.database_queries.boiler_swap.insert_item,
+        .batch(
+            &batch,
             (
-                &id,
-                item.item_type as i8,
-                item.title,
-                item.condition as i8,
-                item.location as i8,
-                item.description,
-                item.emoji as i8,
-                Utc::now().date_naive() + chronoDuration::days(7),
-                604800 * 3,
+                (
+                    &id,
+                    codes.item_type,
+                    item.title,
+                    codes.condition,
+                    codes.location,
+                    item.description,
+                    codes.emoji,
+                    expiration_date,
+                    email,
+                    ItemStatus::Available as i8,
+                    campus,
+                    ttl,
+                ),
+                (email, &id, ttl),
+                (expiration_date, &id, ttl),
             ),
-            fallback_page_state,
         )
         .await?;
 
     Ok(id)
 }
 
-pub fn convert_db_items(row_vec: &Vec<ItemRow>) -> Vec<Item> {
+// Outlives the midnight sweep in expire_ttl by a day so the deletion still reaches CDC
+pub fn expiration_ttl(expiration_date: NaiveDate) -> i32 {
+    (expiration_date + chronoDuration::days(2))
+        .and_hms_opt(0, 0, 0)
+        .expect("midnight is valid")
+        .and_utc()
+        .signed_duration_since(Utc::now())
+        .num_seconds() as i32
+}
+
+pub async fn get_owned_items(state: Arc<AppState>, email: &str) -> Result<Vec<Item>, AppError> {
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_owned_item_ids,
+            (email,),
+            fallback_page_state,
+        )
+        .await?;
+
+    let ids: Vec<Uuid> = returned_rows
+        .into_rows_result()?
+        .rows::<(Uuid,)>()?
+        .map(|row| row.map(|(id,)| id))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    get_items_by_ids(state.clone(), ids, None).await
+}
+
+pub async fn insert_favorite(
+    state: Arc<AppState>,
+    email: &str,
+    item_id: &Uuid,
+    title: &str,
+    notify: bool,
+) -> Result<(), AppError> {
+    let mut batch: Batch = Default::default();
+    batch.append_statement(state.database_queries.boiler_swap.insert_favorite.clone());
+    batch.append_statement(
+        state
+            .database_queries
+            .boiler_swap
+            .insert_item_favorite
+            .clone(),
+    );
+
+    state
+        .database_session
+        .batch(&batch, ((email, item_id), (item_id, email, title, notify)))
+        .await?;
+
+    Ok(())
+}
+
+pub async fn delete_favorite(
+    state: Arc<AppState>,
+    email: &str,
+    item_id: &Uuid,
+) -> Result<(), AppError> {
+    let mut batch: Batch = Default::default();
+    batch.append_statement(state.database_queries.boiler_swap.delete_favorite.clone());
+    batch.append_statement(
+        state
+            .database_queries
+            .boiler_swap
+            .delete_item_favorite
+            .clone(),
+    );
+
+    state
+        .database_session
+        .batch(&batch, ((email, item_id), (item_id, email)))
+        .await?;
+
+    Ok(())
+}
+
+pub async fn get_favorites(state: Arc<AppState>, email: &str) -> Result<Vec<Item>, AppError> {
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_favorite_ids,
+            (email,),
+            fallback_page_state,
+        )
+        .await?;
+
+    let ids: Vec<Uuid> = returned_rows
+        .into_rows_result()?
+        .rows::<(Uuid,)>()?
+        .map(|row| row.map(|(id,)| id))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    get_items_by_ids(
+        state.clone(),
+        ids,
+        Some(&state.config.website_specific.campuses.for_email(email)?.id),
+    )
+    .await
+}
+
+pub async fn take_item_favorites(
+    state: Arc<AppState>,
+    item_id: &Uuid,
+) -> Result<Vec<FavoriteRow>, AppError> {
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_item_favorites,
+            (item_id,),
+            fallback_page_state,
+        )
+        .await?;
+
+    let favorites: Vec<FavoriteRow> = returned_rows
+        .into_rows_result()?
+        .rows::<FavoriteRow>()?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut batch: Batch = Default::default();
+    let mut batch_values: Vec<(&str, &Uuid)> = Vec::new();
+
+    for (email, _, _) in &favorites {
+        batch.append_statement(state.database_queries.boiler_swap.delete_favorite.clone());
+        batch_values.push((email, item_id));
+    }
+
+    if favorites.is_empty() {
+        return Ok(favorites);
+    }
+
+    state.database_session.batch(&batch, &batch_values).await?;
+
+    state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.delete_item_favorites,
+            (item_id,),
+            PagingState::start(),
+        )
+        .await?;
+
+    Ok(favorites)
+}
+
+// Passing the viewer's campus leaves out hidden listings and those from other campuses
+pub async fn get_items_by_ids(
+    state: Arc<AppState>,
+    ids: Vec<Uuid>,
+    campus: Option<&str>,
+) -> Result<Vec<Item>, AppError> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_items_by_ids,
+            (ids,),
+            fallback_page_state,
+        )
+        .await?;
+
+    let row_result = returned_rows.into_rows_result()?;
+
+    let today = Utc::now().date_naive();
+
+    let row_vec: Vec<ItemRow> = row_result
+        .rows::<ItemRow>()?
+        .filter(|row| {
+            !matches!(
+                row,
+                Ok((_, _, _, _, _, _, _, expiration_date, _, _, _, _)) if *expiration_date < today
+            )
+        })
+        .filter(|row| {
+            !matches!(
+                (row, campus),
+                (Ok((_, _, _, _, _, _, _, _, _, _, hidden, item_campus)), Some(campus))
+                    if *hidden == Some(true)
+                        || item_campus.unwrap_or(LEGACY_CAMPUS) != campus
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(convert_db_items(&row_vec, &read_catalog(&state.catalog)))
+}
+
+pub async fn delete_owned_item(
+    state: Arc<AppState>,
+    email: &str,
+    item_id: &Uuid,
+) -> Result<(), AppError> {
+    let fallback_page_state = PagingState::start();
+
+    state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.delete_owned_item,
+            (email, item_id),
+            fallback_page_state,
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub fn convert_db_items(row_vec: &Vec<ItemRow>, catalog: &Catalog) -> Vec<Item> {
     row_vec
         .iter()
         .map(
@@ -50,112 +316,1891 @@ pub fn convert_db_items(row_vec: &Vec<ItemRow>) -> Vec<Item> {
                 description,
                 emoji_i8,
                 expiration_date,
+                photos,
+                status_i8,
+                _hidden,
+                campus,
             )| Item {
                 item_id: *id,
-                item_type: ItemType::try_from(convert_i8_to_u8(item_type_i8))
-                    .unwrap_or(ItemType::Other)
-                    .as_ref()
-                    .to_string(),
+                item_type: catalog.name(CatalogKind::ItemType, item_type_i8),
                 title: title.to_string(),
-                condition: Condition::try_from(convert_i8_to_u8(condition_i8))
-                    .unwrap_or(Condition::Fair)
-                    .as_ref()
-                    .to_string(),
-                location: Location::try_from(convert_i8_to_u8(location_i8))
-                    .unwrap_or(Location::CaryQuadEast)
-                    .as_ref()
-                    .to_string(),
+                condition: catalog.name(CatalogKind::Condition, condition_i8),
+                location: catalog.name(CatalogKind::Location, location_i8),
                 description: description.to_string(),
-                emoji: Emoji::try_from(convert_i8_to_u8(emoji_i8))
-                    .unwrap_or(Emoji::Books)
+                emoji: catalog.name(CatalogKind::Emoji, emoji_i8),
+                expiration_date: expiration_date.format("%Y-%m-%d").to_string(),
+                photos: photos.clone().unwrap_or_default(),
+                status: ItemStatus::try_from(convert_i8_to_u8(&status_i8.unwrap_or(0)))
+                    .unwrap_or(ItemStatus::Available)
                     .as_ref()
                     .to_string(),
-                expiration_date: expiration_date.format("%Y-%m-%d").to_string(),
+                campus: campus.unwrap_or(LEGACY_CAMPUS).to_string(),
+                geo: catalog.geo(location_i8),
             },
         )
         .collect()
 }
 
-pub async fn get_user(
+pub async fn insert_report(
     state: Arc<AppState>,
+    item_id: &Uuid,
     email: &str,
-    website_path: &WebsitePath,
-) -> Result<Option<(String, bool)>, AppError> {
-    is_this_for_swap(website_path);
+    reason: ReportReason,
+) -> Result<i64, AppError> {
+    state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.insert_report,
+            (item_id, email, reason as i8),
+            PagingState::start(),
+        )
+        .await?;
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.count_reports,
+            (item_id,),
+            PagingState::start(),
+        )
+        .await?;
+
+    Ok(returned_rows.into_rows_result()?.first_row::<(i64,)>()?.0)
+}
+
+pub async fn hide_item(state: Arc<AppState>, item_id: &Uuid, reports: i64) -> Result<(), AppError> {
+    let ttl = get_item_ttl(state.clone(), item_id).await?;
+
+    if ttl <= 0 {
+        return Ok(());
+    }
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.hide_item,
+            (ttl, true, item_id),
+            PagingState::start(),
+        )
+        .await?;
+
+    if !was_applied(returned_rows.into_rows_result()?.first_row::<Row>()?) {
+        return Ok(());
+    }
+
+    state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.insert_moderation_item,
+            (item_id, reports, Utc::now()),
+            PagingState::start(),
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub async fn unhide_item(state: Arc<AppState>, item_id: &Uuid) -> Result<(), AppError> {
+    let ttl = get_item_ttl(state.clone(), item_id).await?;
+
+    if ttl <= 0 {
+        return Err(AppError::BadRequest("Item not found".to_string()));
+    }
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.hide_item,
+            (ttl, false, item_id),
+            PagingState::start(),
+        )
+        .await?;
+
+    if !was_applied(returned_rows.into_rows_result()?.first_row::<Row>()?) {
+        return Err(AppError::BadRequest("Item not found".to_string()));
+    }
+
+    delete_reports(state.clone(), item_id).await
+}
+
+pub async fn delete_reports(state: Arc<AppState>, item_id: &Uuid) -> Result<(), AppError> {
+    let mut batch: Batch = Default::default();
+    batch.append_statement(state.database_queries.boiler_swap.delete_reports.clone());
+    batch.append_statement(
+        state
+            .database_queries
+            .boiler_swap
+            .delete_moderation_item
+            .clone(),
+    );
+
+    state
+        .database_session
+        .batch(&batch, ((item_id,), (item_id,)))
+        .await?;
+
+    Ok(())
+}
+
+pub async fn get_moderation_queue(state: Arc<AppState>) -> Result<Vec<ModerationRow>, AppError> {
+    let mut paging_state = PagingState::start();
+    let mut queue = Vec::new();
+
+    loop {
+        let (query_result, paging_state_response) = state
+            .database_session
+            .execute_single_page(
+                &state.database_queries.boiler_swap.get_moderation_queue,
+                &[],
+                paging_state,
+            )
+            .await?;
+
+        queue.extend(
+            query_result
+                .into_rows_result()?
+                .rows::<ModerationRow>()?
+                .collect::<Result<Vec<_>, _>>()?,
+        );
+
+        match paging_state_response.into_paging_control_flow() {
+            ControlFlow::Break(()) => {
+                break;
+            }
+            ControlFlow::Continue(new_paging_state) => paging_state = new_paging_state,
+        }
+    }
+
+    Ok(queue)
+}
+
+pub async fn get_admin_item_page(
+    state: Arc<AppState>,
+    paging_state: PagingState,
+) -> Result<(Vec<AdminItemRow>, Option<PagingState>), AppError> {
+    let (query_result, paging_state_response) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_admin_items,
+            &[],
+            paging_state,
+        )
+        .await?;
+
+    let rows: Vec<AdminItemRow> = query_result
+        .into_rows_result()?
+        .rows::<AdminItemRow>()?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((
+        rows,
+        match paging_state_response.into_paging_control_flow() {
+            ControlFlow::Break(()) => None,
+            ControlFlow::Continue(new_paging_state) => Some(new_paging_state),
+        },
+    ))
+}
 
+pub async fn get_item_owner(
+    state: Arc<AppState>,
+    item_id: &Uuid,
+) -> Result<Option<String>, AppError> {
     let fallback_page_state = PagingState::start();
 
     let (returned_rows, _) = state
         .database_session
         .execute_single_page(
-            &state.database_queries.boiler_swap.get_user,
-            (email,),
+            &state.database_queries.boiler_swap.get_item_owner,
+            (item_id,),
             fallback_page_state,
         )
         .await?;
 
     match returned_rows
         .into_rows_result()?
-        .first_row::<(String, bool)>()
+        .first_row::<(Option<String>,)>()
     {
-        Ok((password_hash, locked)) => Ok(Some((password_hash, locked))),
+        Ok((owner,)) => Ok(owner),
         Err(RowsEmpty) => Ok(None),
         Err(e) => Err(e.into()),
     }
 }
 
-pub async fn insert_user(
-    state: Arc<AppState>,
-    account: &RedisAccount,
-    website_path: &WebsitePath,
-) -> Result<(), AppError> {
-    is_this_for_swap(website_path);
-
+pub async fn get_item_ttl(state: Arc<AppState>, item_id: &Uuid) -> Result<i32, AppError> {
     let fallback_page_state = PagingState::start();
 
-    state
+    let (returned_rows, _) = state
         .database_session
         .execute_single_page(
-            &state.database_queries.boiler_swap.insert_user,
-            (account.email.clone(), account.password_hash.clone(), false),
+            &state.database_queries.boiler_swap.get_item_ttl,
+            (item_id,),
             fallback_page_state,
         )
         .await?;
 
-    Ok(())
+    match returned_rows
+        .into_rows_result()?
+        .first_row::<(Option<i32>,)>()
+    {
+        Ok((ttl,)) => Ok(ttl.unwrap_or(0)),
+        Err(RowsEmpty) => Ok(0),
+        Err(e) => Err(e.into()),
+    }
 }
 
-fn is_this_for_swap(website_path: &WebsitePath) {
-    match website_path {
-        WebsitePath::BoilerSwap => {}
-        WebsitePath::Photos => panic!("Photos not implemented"),
-        WebsitePath::Home => panic!("Home not implemented"),
-    }
+// Conditional updates lead their result with the [applied] column
+pub fn was_applied(row: Row) -> bool {
+    matches!(row.columns.first(), Some(Some(CqlValue::Boolean(true))))
 }
 
-pub async fn check_lock(
+pub async fn update_item(
     state: Arc<AppState>,
-    email: &str,
-    website_path: &WebsitePath,
-) -> Result<Option<bool>, AppError> {
-    is_this_for_swap(website_path);
+    item_id: &Uuid,
+    item: ItemPayload,
+    campus: &str,
+) -> Result<(), AppError> {
+    let codes = read_catalog(&state.catalog)
+        .item_codes(&item, campus)
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+    let ttl = get_item_ttl(state.clone(), item_id).await?;
+
+    if ttl <= 0 {
+        return Err(AppError::Unauthorized("Unable to verify".to_string()));
+    }
 
     let fallback_page_state = PagingState::start();
 
     let (returned_rows, _) = state
         .database_session
         .execute_single_page(
-            &state.database_queries.boiler_swap.check_lock,
-            (email,),
+            &state.database_queries.boiler_swap.update_item,
+            (
+                ttl,
+                codes.item_type,
+                item.title,
+                codes.condition,
+                codes.location,
+                item.description,
+                codes.emoji,
+                item_id,
+            ),
             fallback_page_state,
         )
         .await?;
 
-    match returned_rows.into_rows_result()?.first_row::<(bool,)>() {
-        Ok((locked,)) => Ok(Some(locked)),
-        Err(RowsEmpty) => Ok(None),
-        Err(e) => Err(e.into()),
+    if !was_applied(returned_rows.into_rows_result()?.first_row::<Row>()?) {
+        return Err(AppError::Unauthorized("Unable to verify".to_string()));
     }
+
+    Ok(())
+}
+
+pub async fn renew_item(state: Arc<AppState>, item_id: &Uuid) -> Result<ItemRenewal, AppError> {
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_renewal_item,
+            (item_id,),
+            fallback_page_state,
+        )
+        .await?;
+
+    let (
+        item_type,
+        title,
+        condition,
+        location,
+        description,
+        emoji,
+        current_expiration,
+        owner,
+        photos,
+        status,
+        hidden,
+        renewals,
+        campus,
+    ) = match returned_rows.into_rows_result()?.first_row::<RenewalRow>() {
+        Ok(row) => row,
+        Err(RowsEmpty) => return Err(AppError::Unauthorized("Unable to verify".to_string())),
+        Err(e) => return Err(e.into()),
+    };
+
+    let renewals = convert_i8_to_u8(&renewals.unwrap_or(0));
+    let max_renewals = state.config.website_specific.max_renewals;
+
+    if renewals >= max_renewals {
+        return Err(AppError::BadRequest("Renewal limit reached".to_string()));
+    }
+
+    let expiration_date = Utc::now().date_naive()
+        + chronoDuration::days(
+            state
+                .config
+                .website_specific
+                .expiration_days
+                .days(&read_catalog(&state.catalog).name(CatalogKind::ItemType, &item_type))
+                as i64,
+        );
+
+    if expiration_date <= current_expiration {
+        return Err(AppError::BadRequest(
+            "Listing is not due for renewal".to_string(),
+        ));
+    }
+
+    let ttl = expiration_ttl(expiration_date);
+    let photo_ids = photos.clone().unwrap_or_default();
+
+    // Every renewal moves the expiration date, so the condition fails if the listing was
+    // deleted, swept or renewed since it was read
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.renew_item,
+            (
+                ttl,
+                item_type,
+                title,
+                condition,
+                location,
+                description,
+                emoji,
+                expiration_date,
+                &owner,
+                photos,
+                status,
+                hidden,
+                (renewals + 1) as i8,
+                campus,
+                item_id,
+                current_expiration,
+            ),
+            PagingState::start(),
+        )
+        .await?;
+
+    if !was_applied(returned_rows.into_rows_result()?.first_row::<Row>()?) {
+        return Err(AppError::Unauthorized("Unable to verify".to_string()));
+    }
+
+    let mut batch: Batch = Default::default();
+    batch.append_statement(state.database_queries.boiler_swap.insert_owned_item.clone());
+    batch.append_statement(
+        state
+            .database_queries
+            .boiler_swap
+            .insert_expiring_item
+            .clone(),
+    );
+
+    state
+        .database_session
+        .batch(
+            &batch,
+            ((&owner, item_id, ttl), (expiration_date, item_id, ttl)),
+        )
+        .await?;
+
+    for photo_id in photo_ids {
+        if let Some(photo) = get_item_photo(state.clone(), item_id, &photo_id).await? {
+            state
+                .database_session
+                .execute_single_page(
+                    &state.database_queries.boiler_swap.insert_item_photo,
+                    (item_id, &photo_id, photo, ttl),
+                    PagingState::start(),
+                )
+                .await?;
+        }
+    }
+
+    Ok(ItemRenewal {
+        expiration_date: expiration_date.format("%Y-%m-%d").to_string(),
+        renewals_remaining: max_renewals - renewals - 1,
+    })
+}
+
+pub async fn update_item_status(
+    state: Arc<AppState>,
+    item_id: &Uuid,
+    status: ItemStatus,
+) -> Result<(), AppError> {
+    let ttl = get_item_ttl(state.clone(), item_id).await?;
+
+    if ttl <= 0 {
+        return Err(AppError::Unauthorized("Unable to verify".to_string()));
+    }
+
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.update_item_status,
+            (ttl, status as i8, item_id),
+            fallback_page_state,
+        )
+        .await?;
+
+    if !was_applied(returned_rows.into_rows_result()?.first_row::<Row>()?) {
+        return Err(AppError::Unauthorized("Unable to verify".to_string()));
+    }
+
+    Ok(())
+}
+
+pub async fn get_item_photo_ids(
+    state: Arc<AppState>,
+    item_id: &Uuid,
+) -> Result<Vec<Uuid>, AppError> {
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_item_photo_ids,
+            (item_id,),
+            fallback_page_state,
+        )
+        .await?;
+
+    match returned_rows
+        .into_rows_result()?
+        .first_row::<(Option<Vec<Uuid>>,)>()
+    {
+        Ok((photos,)) => Ok(photos.unwrap_or_default()),
+        Err(RowsEmpty) => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub async fn insert_item_photo(
+    state: Arc<AppState>,
+    item_id: &Uuid,
+    photo_bytes: Vec<u8>,
+) -> Result<Uuid, AppError> {
+    let ttl = get_item_ttl(state.clone(), item_id).await?;
+
+    if ttl <= 0 {
+        return Err(AppError::Unauthorized("Unable to verify".to_string()));
+    }
+
+    let photo_id = Uuid::new_v4();
+
+    // Conditional writes cannot share a batch with another table, so the item is claimed first
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.add_item_photo,
+            (ttl, vec![photo_id], item_id),
+            PagingState::start(),
+        )
+        .await?;
+
+    if !was_applied(returned_rows.into_rows_result()?.first_row::<Row>()?) {
+        return Err(AppError::Unauthorized("Unable to verify".to_string()));
+    }
+
+    state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.insert_item_photo,
+            (item_id, &photo_id, photo_bytes, ttl),
+            PagingState::start(),
+        )
+        .await?;
+
+    Ok(photo_id)
+}
+
+pub async fn get_item_photo(
+    state: Arc<AppState>,
+    item_id: &Uuid,
+    photo_id: &Uuid,
+) -> Result<Option<Vec<u8>>, AppError> {
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_item_photo,
+            (item_id, photo_id),
+            fallback_page_state,
+        )
+        .await?;
+
+    match returned_rows.into_rows_result()?.first_row::<(Vec<u8>,)>() {
+        Ok((photo,)) => Ok(Some(photo)),
+        Err(RowsEmpty) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub async fn delete_item_photos(state: Arc<AppState>, item_id: &Uuid) -> Result<(), AppError> {
+    let fallback_page_state = PagingState::start();
+
+    state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.delete_item_photos,
+            (item_id,),
+            fallback_page_state,
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub async fn delete_item(state: Arc<AppState>, item_id: &Uuid) -> Result<(), AppError> {
+    let fallback_page_state = PagingState::start();
+
+    state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.delete_item,
+            (item_id,),
+            fallback_page_state,
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub async fn insert_thread(
+    state: Arc<AppState>,
+    thread_id: &Uuid,
+    item_id: &Uuid,
+    seller: &str,
+    buyer: &str,
+) -> Result<(), AppError> {
+    let mut batch: Batch = Default::default();
+    batch.append_statement(
+        state
+            .database_queries
+            .boiler_swap
+            .insert_user_thread
+            .clone(),
+    );
+    batch.append_statement(
+        state
+            .database_queries
+            .boiler_swap
+            .insert_user_thread
+            .clone(),
+    );
+
+    state
+        .database_session
+        .batch(
+            &batch,
+            (
+                (seller, thread_id, item_id, buyer, ThreadRole::Seller as i8),
+                (buyer, thread_id, item_id, seller, ThreadRole::Buyer as i8),
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub async fn get_threads(state: Arc<AppState>, email: &str) -> Result<Vec<Thread>, AppError> {
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_user_threads,
+            (email,),
+            fallback_page_state,
+        )
+        .await?;
+
+    let row_vec: Vec<ThreadRow> = returned_rows
+        .into_rows_result()?
+        .rows::<ThreadRow>()?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(row_vec
+        .into_iter()
+        .map(|(thread_id, item_id, role_i8)| Thread {
+            thread_id,
+            item_id,
+            role: ThreadRole::try_from(convert_i8_to_u8(&role_i8))
+                .unwrap_or(ThreadRole::Buyer)
+                .as_ref()
+                .to_string(),
+        })
+        .collect())
+}
+
+pub async fn get_thread_recipient(
+    state: Arc<AppState>,
+    email: &str,
+    thread_id: &Uuid,
+) -> Result<Option<String>, AppError> {
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_user_thread,
+            (email, thread_id),
+            fallback_page_state,
+        )
+        .await?;
+
+    match returned_rows.into_rows_result()?.first_row::<(String,)>() {
+        Ok((other_email,)) => Ok(Some(other_email)),
+        Err(RowsEmpty) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub async fn insert_message(
+    state: Arc<AppState>,
+    thread_id: &Uuid,
+    email: &str,
+    body: &str,
+) -> Result<(Uuid, DateTime<Utc>), AppError> {
+    let fallback_page_state = PagingState::start();
+    let message_id = Uuid::new_v4();
+    let sent_at = Utc::now();
+
+    state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.insert_message,
+            (thread_id, sent_at, &message_id, email, body),
+            fallback_page_state,
+        )
+        .await?;
+
+    Ok((message_id, sent_at))
+}
+
+pub async fn get_messages(
+    state: Arc<AppState>,
+    email: &str,
+    thread_id: &Uuid,
+) -> Result<Vec<Message>, AppError> {
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_messages,
+            (thread_id,),
+            fallback_page_state,
+        )
+        .await?;
+
+    let row_result = returned_rows.into_rows_result()?;
+
+    let row_vec: Vec<MessageRow> = row_result
+        .rows::<MessageRow>()?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(row_vec
+        .into_iter()
+        .map(|(sent_at, message_id, sender, body)| Message {
+            thread_id: *thread_id,
+            message_id,
+            body: body.to_string(),
+            sent_at,
+            from_me: sender == email,
+        })
+        .collect())
+}
+
+pub async fn insert_saved_search(
+    state: Arc<AppState>,
+    email: &str,
+    search: SavedSearchPayload,
+) -> Result<Uuid, AppError> {
+    let codes = {
+        let catalog = read_catalog(&state.catalog);
+
+        (
+            search
+                .item_type
+                .map(|name| catalog.code(CatalogKind::ItemType, &name))
+                .transpose()
+                .map_err(|e| AppError::BadRequest(e.to_string()))?,
+            search
+                .condition
+                .map(|name| catalog.code(CatalogKind::Condition, &name))
+                .transpose()
+                .map_err(|e| AppError::BadRequest(e.to_string()))?,
+            search
+                .location
+                .map(|name| catalog.code(CatalogKind::Location, &name))
+                .transpose()
+                .map_err(|e| AppError::BadRequest(e.to_string()))?,
+        )
+    };
+    let campus = &state.config.website_specific.campuses.for_email(email)?.id;
+    let search_id = Uuid::new_v4();
+
+    let mut batch: Batch = Default::default();
+    batch.append_statement(
+        state
+            .database_queries
+            .boiler_swap
+            .insert_saved_search
+            .clone(),
+    );
+    batch.append_statement(
+        state
+            .database_queries
+            .boiler_swap
+            .insert_alert_search
+            .clone(),
+    );
+
+    state
+        .database_session
+        .batch(
+            &batch,
+            (
+                (email, &search_id, &search.query, codes.0, codes.1, codes.2),
+                (
+                    campus,
+                    codes.0.unwrap_or(saved_searches_by_type::ANY_ITEM_TYPE),
+                    email,
+                    &search_id,
+                    &search.query,
+                    codes.1,
+                    codes.2,
+                    saved_search_ttl(),
+                ),
+            ),
+        )
+        .await?;
+
+    Ok(search_id)
+}
+
+pub fn saved_search_ttl() -> i32 {
+    saved_searches::TTL
+        .parse()
+        .expect("saved search TTL is a number")
+}
+
+pub async fn get_saved_searches(
+    state: Arc<AppState>,
+    email: &str,
+) -> Result<Vec<SavedSearch>, AppError> {
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_saved_searches,
+            (email,),
+            fallback_page_state,
+        )
+        .await?;
+
+    let row_result = returned_rows.into_rows_result()?;
+
+    let row_vec: Vec<SavedSearchRow> = row_result
+        .rows::<SavedSearchRow>()?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(
+        convert_saved_searches(&row_vec, &read_catalog(&state.catalog))
+            .into_iter()
+            .map(|(_, search)| search)
+            .collect(),
+    )
+}
+
+pub fn convert_saved_searches(
+    row_vec: &[SavedSearchRow],
+    catalog: &Catalog,
+) -> Vec<(String, SavedSearch)> {
+    row_vec
+        .iter()
+        .map(
+            |(email, search_id, query, item_type_i8, condition_i8, location_i8)| {
+                (
+                    email.to_string(),
+                    SavedSearch {
+                        search_id: *search_id,
+                        query: query.to_string(),
+                        item_type: item_type_i8
+                            .and_then(|i| catalog.try_name(CatalogKind::ItemType, &i)),
+                        condition: condition_i8
+                            .and_then(|i| catalog.try_name(CatalogKind::Condition, &i)),
+                        location: location_i8
+                            .and_then(|i| catalog.try_name(CatalogKind::Location, &i)),
+                    },
+                )
+            },
+        )
+        .collect()
+}
+
+// Alert matching reads searches by (campus, item_type), so both copies are kept in step
+pub async fn get_alert_searches(
+    state: Arc<AppState>,
+    campus: &str,
+    item_type: i8,
+) -> Result<Vec<(String, SavedSearch)>, AppError> {
+    let mut paging_state = PagingState::start();
+    let mut searches = Vec::new();
+
+    loop {
+        let (query_result, paging_state_response) = state
+            .database_session
+            .execute_single_page(
+                &state.database_queries.boiler_swap.get_alert_searches,
+                (campus, item_type),
+                paging_state,
+            )
+            .await?;
+
+        let row_result = query_result.into_rows_result()?;
+
+        let row_vec: Vec<SavedSearchRow> = row_result
+            .rows::<SavedSearchRow>()?
+            .map(|row| {
+                row.map(
+                    |(email, search_id, query, item_type, condition, location)| {
+                        (
+                            email,
+                            search_id,
+                            query,
+                            item_type.filter(|i| *i != saved_searches_by_type::ANY_ITEM_TYPE),
+                            condition,
+                            location,
+                        )
+                    },
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        searches.extend(convert_saved_searches(
+            &row_vec,
+            &read_catalog(&state.catalog),
+        ));
+
+        match paging_state_response.into_paging_control_flow() {
+            ControlFlow::Break(()) => {
+                break;
+            }
+            ControlFlow::Continue(new_paging_state) => paging_state = new_paging_state,
+        }
+    }
+
+    Ok(searches)
+}
+
+pub async fn delete_saved_search(
+    state: Arc<AppState>,
+    email: &str,
+    search_id: &Uuid,
+) -> Result<(), AppError> {
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_saved_search_type,
+            (email, search_id),
+            fallback_page_state,
+        )
+        .await?;
+
+    let item_type = match returned_rows
+        .into_rows_result()?
+        .first_row::<(Option<i8>,)>()
+    {
+        Ok((item_type,)) => item_type.unwrap_or(saved_searches_by_type::ANY_ITEM_TYPE),
+        Err(RowsEmpty) => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let campus = &state.config.website_specific.campuses.for_email(email)?.id;
+
+    let mut batch: Batch = Default::default();
+    batch.append_statement(
+        state
+            .database_queries
+            .boiler_swap
+            .delete_saved_search
+            .clone(),
+    );
+    batch.append_statement(
+        state
+            .database_queries
+            .boiler_swap
+            .delete_alert_search
+            .clone(),
+    );
+
+    state
+        .database_session
+        .batch(
+            &batch,
+            ((email, search_id), (campus, item_type, email, search_id)),
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub async fn get_user(
+    state: Arc<AppState>,
+    email: &str,
+    website_path: &WebsitePath,
+) -> Result<Option<(String, bool)>, AppError> {
+    is_this_for_swap(website_path);
+
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_user,
+            (email,),
+            fallback_page_state,
+        )
+        .await?;
+
+    match returned_rows
+        .into_rows_result()?
+        .first_row::<(String, bool, Option<i8>)>()
+    {
+        Ok((password_hash, locked, role_i8)) => {
+            Ok(Some((password_hash, locked || is_banned(role_i8))))
+        }
+        Err(RowsEmpty) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub async fn get_totp(
+    state: Arc<AppState>,
+    email: &str,
+) -> Result<Option<(String, Vec<String>)>, AppError> {
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_totp,
+            (email,),
+            fallback_page_state,
+        )
+        .await?;
+
+    match returned_rows
+        .into_rows_result()?
+        .first_row::<(Option<String>, Option<Vec<String>>)>()
+    {
+        Ok((Some(secret), recovery_codes)) => {
+            Ok(Some((secret, recovery_codes.unwrap_or_default())))
+        }
+        Ok((None, _)) | Err(RowsEmpty) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub async fn update_totp(
+    state: Arc<AppState>,
+    email: &str,
+    secret: Option<&str>,
+    recovery_codes: Option<Vec<String>>,
+) -> Result<(), AppError> {
+    let fallback_page_state = PagingState::start();
+
+    state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.update_totp,
+            (secret, recovery_codes, email),
+            fallback_page_state,
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub async fn remove_recovery_code(
+    state: Arc<AppState>,
+    email: &str,
+    recovery_code: &str,
+) -> Result<(), AppError> {
+    let fallback_page_state = PagingState::start();
+
+    state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.remove_recovery_code,
+            (vec![recovery_code], email),
+            fallback_page_state,
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub async fn insert_passkey(
+    state: Arc<AppState>,
+    email: &str,
+    credential_id: &str,
+    public_key: Vec<u8>,
+    sign_count: i64,
+) -> Result<(), AppError> {
+    let mut batch: Batch = Default::default();
+    batch.append_statement(state.database_queries.boiler_swap.insert_passkey.clone());
+    batch.append_statement(
+        state
+            .database_queries
+            .boiler_swap
+            .insert_user_passkey
+            .clone(),
+    );
+
+    state
+        .database_session
+        .batch(
+            &batch,
+            (
+                (credential_id, email, public_key, sign_count),
+                (email, credential_id, Utc::now()),
+            ),
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub async fn get_passkey(
+    state: Arc<AppState>,
+    credential_id: &str,
+) -> Result<Option<PasskeyRow>, AppError> {
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_passkey,
+            (credential_id,),
+            fallback_page_state,
+        )
+        .await?;
+
+    match returned_rows.into_rows_result()?.first_row::<PasskeyRow>() {
+        Ok(row) => Ok(Some(row)),
+        Err(RowsEmpty) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub async fn get_passkeys(state: Arc<AppState>, email: &str) -> Result<Vec<Passkey>, AppError> {
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_user_passkeys,
+            (email,),
+            fallback_page_state,
+        )
+        .await?;
+
+    Ok(returned_rows
+        .into_rows_result()?
+        .rows::<(String, DateTime<Utc>)>()?
+        .map(|row| {
+            row.map(|(credential_id, created_at)| Passkey {
+                credential_id,
+                created_at,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?)
+}
+
+pub async fn update_sign_count(
+    state: Arc<AppState>,
+    credential_id: &str,
+    sign_count: i64,
+) -> Result<(), AppError> {
+    let fallback_page_state = PagingState::start();
+
+    state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.update_sign_count,
+            (sign_count, credential_id),
+            fallback_page_state,
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub async fn delete_passkey(
+    state: Arc<AppState>,
+    email: &str,
+    credential_id: &str,
+) -> Result<(), AppError> {
+    let mut batch: Batch = Default::default();
+    batch.append_statement(state.database_queries.boiler_swap.delete_passkey.clone());
+    batch.append_statement(
+        state
+            .database_queries
+            .boiler_swap
+            .delete_user_passkey
+            .clone(),
+    );
+
+    state
+        .database_session
+        .batch(&batch, ((credential_id,), (email, credential_id)))
+        .await?;
+
+    Ok(())
+}
+
+pub async fn migrate_user(
+    state: Arc<AppState>,
+    email: &str,
+    new_email: &str,
+) -> Result<Vec<Uuid>, AppError> {
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_user_row,
+            (email,),
+            fallback_page_state,
+        )
+        .await?;
+
+    let (password_hash, locked, role, totp_secret, recovery_codes) =
+        match returned_rows.into_rows_result()?.first_row::<UserRow>() {
+            Ok(row) => row,
+            Err(RowsEmpty) => return Err(AppError::BadRequest("User not found".to_string())),
+            Err(e) => return Err(e.into()),
+        };
+
+    let campuses = &state.config.website_specific.campuses;
+    let campus = &campuses.for_email(email)?.id;
+
+    // Listings keep the campus they were posted under, so the account cannot change schools
+    if *campus != campuses.for_email(new_email)?.id {
+        return Err(AppError::BadRequest(
+            "Email must stay on the same campus".to_string(),
+        ));
+    }
+
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_owned_item_ids,
+            (email,),
+            fallback_page_state,
+        )
+        .await?;
+
+    let item_ids: Vec<Uuid> = returned_rows
+        .into_rows_result()?
+        .rows::<(Uuid,)>()?
+        .map(|row| row.map(|(id,)| id))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let expiration_dates: Vec<(Uuid, NaiveDate)> = if item_ids.is_empty() {
+        Vec::new()
+    } else {
+        let fallback_page_state = PagingState::start();
+
+        let (returned_rows, _) = state
+            .database_session
+            .execute_single_page(
+                &state.database_queries.boiler_swap.get_expiration_dates,
+                (&item_ids,),
+                fallback_page_state,
+            )
+            .await?;
+
+        returned_rows
+            .into_rows_result()?
+            .rows::<(Uuid, NaiveDate)>()?
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let passkeys = get_passkeys(state.clone(), email).await?;
+    let favorites = get_user_favorites(state.clone(), email).await?;
+    let searches = get_user_saved_searches(state.clone(), email).await?;
+    let threads = get_user_thread_rows(state.clone(), email).await?;
+
+    // Claimed first so a signup racing the change cannot take the address
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.insert_migrated_user,
+            (
+                new_email,
+                &password_hash,
+                locked,
+                role,
+                totp_secret,
+                recovery_codes,
+            ),
+            PagingState::start(),
+        )
+        .await?;
+
+    let claimed = was_applied(returned_rows.into_rows_result()?.first_row::<Row>()?);
+
+    // An interrupted change leaves a claim with the same password hash, which is resumed
+    if !claimed && !is_migration_claim(state.clone(), new_email, &password_hash).await? {
+        return Err(AppError::BadRequest("Email already in use".to_string()));
+    }
+
+    let queries = &state.database_queries.boiler_swap;
+    let mut copies: Vec<(PreparedStatement, BoxedRow)> = Vec::new();
+    let mut switches: Vec<(PreparedStatement, BoxedRow)> = Vec::new();
+    let mut moved_threads: Vec<Uuid> = Vec::new();
+
+    // Only items still on the books move, stale mappings are dropped with the old partition
+    for (item_id, expiration_date) in &expiration_dates {
+        copies.push((
+            queries.insert_owned_item.clone(),
+            Box::new((new_email, *item_id, expiration_ttl(*expiration_date))),
+        ));
+    }
+
+    for passkey in &passkeys {
+        copies.push((
+            queries.insert_user_passkey.clone(),
+            Box::new((new_email, passkey.credential_id.clone(), passkey.created_at)),
+        ));
+        switches.push((
+            queries.update_passkey_owner.clone(),
+            Box::new((new_email, passkey.credential_id.clone())),
+        ));
+    }
+
+    for (item_id, title, notify) in &favorites {
+        copies.push((
+            queries.insert_favorite.clone(),
+            Box::new((new_email, *item_id)),
+        ));
+        copies.push((
+            queries.insert_item_favorite.clone(),
+            Box::new((*item_id, new_email, title.clone(), *notify)),
+        ));
+    }
+
+    for (search_id, query, item_type, condition, location) in &searches {
+        copies.push((
+            queries.insert_saved_search.clone(),
+            Box::new((
+                new_email,
+                *search_id,
+                query.clone(),
+                *item_type,
+                *condition,
+                *location,
+            )),
+        ));
+        copies.push((
+            queries.insert_alert_search.clone(),
+            Box::new((
+                campus,
+                item_type.unwrap_or(saved_searches_by_type::ANY_ITEM_TYPE),
+                new_email,
+                *search_id,
+                query.clone(),
+                *condition,
+                *location,
+                saved_search_ttl(),
+            )),
+        ));
+    }
+
+    // Buyer threads are keyed by Uuid::new_v5(item_id, buyer), so they move to a new id
+    for (thread_id, item_id, other_email, role) in &threads {
+        let is_buyer = *role == ThreadRole::Buyer as i8;
+        let new_thread_id = if is_buyer {
+            Uuid::new_v5(item_id, new_email.as_bytes())
+        } else {
+            *thread_id
+        };
+
+        for (sent_at, message_id, sender, body, ttl) in
+            get_thread_messages(state.clone(), thread_id).await?
+        {
+            let from_user = sender == email;
+
+            if new_thread_id == *thread_id && !from_user {
+                continue;
+            }
+
+            let row: BoxedRow = Box::new((
+                new_thread_id,
+                sent_at,
+                message_id,
+                if from_user {
+                    new_email.to_string()
+                } else {
+                    sender
+                },
+                body,
+                ttl.unwrap_or(0),
+            ));
+
+            // Rewriting the sender in place is only safe once the account has moved
+            if new_thread_id == *thread_id {
+                switches.push((queries.insert_migrated_message.clone(), row));
+            } else {
+                copies.push((queries.insert_migrated_message.clone(), row));
+            }
+        }
+
+        copies.push((
+            queries.insert_user_thread.clone(),
+            Box::new((
+                new_email,
+                new_thread_id,
+                *item_id,
+                other_email.clone(),
+                *role,
+            )),
+        ));
+        switches.push((
+            queries.insert_user_thread.clone(),
+            Box::new((
+                other_email.clone(),
+                new_thread_id,
+                *item_id,
+                new_email,
+                if is_buyer {
+                    ThreadRole::Seller as i8
+                } else {
+                    ThreadRole::Buyer as i8
+                },
+            )),
+        ));
+        switches.push((
+            queries.delete_user_thread.clone(),
+            Box::new((email, *thread_id)),
+        ));
+
+        if new_thread_id != *thread_id {
+            switches.push((
+                queries.delete_user_thread.clone(),
+                Box::new((other_email.clone(), *thread_id)),
+            ));
+            moved_threads.push(*thread_id);
+        }
+    }
+
+    // Copies only add rows under the new address, which the user has already confirmed owning,
+    // so releasing the claim is enough to undo a failed attempt
+    if let Err(e) = batch_in_chunks(state.clone(), &copies).await {
+        if claimed {
+            state
+                .database_session
+                .execute_single_page(&queries.delete_user, (new_email,), PagingState::start())
+                .await?;
+        }
+
+        return Err(e);
+    }
+
+    // Conditional, so a listing deleted or swept meanwhile is not recreated as an owner-only row
+    for (item_id, expiration_date) in &expiration_dates {
+        state
+            .database_session
+            .execute_single_page(
+                &queries.update_item_owner,
+                (expiration_ttl(*expiration_date), new_email, item_id),
+                PagingState::start(),
+            )
+            .await?;
+    }
+
+    for item_id in &item_ids {
+        switches.push((
+            queries.delete_owned_item.clone(),
+            Box::new((email, *item_id)),
+        ));
+    }
+
+    for passkey in &passkeys {
+        switches.push((
+            queries.delete_user_passkey.clone(),
+            Box::new((email, passkey.credential_id.clone())),
+        ));
+    }
+
+    for (item_id, _, _) in &favorites {
+        switches.push((queries.delete_favorite.clone(), Box::new((email, *item_id))));
+        switches.push((
+            queries.delete_item_favorite.clone(),
+            Box::new((*item_id, email)),
+        ));
+    }
+
+    for (search_id, _, item_type, _, _) in &searches {
+        switches.push((
+            queries.delete_saved_search.clone(),
+            Box::new((email, *search_id)),
+        ));
+        switches.push((
+            queries.delete_alert_search.clone(),
+            Box::new((
+                campus,
+                item_type.unwrap_or(saved_searches_by_type::ANY_ITEM_TYPE),
+                email,
+                *search_id,
+            )),
+        ));
+    }
+
+    for thread_id in moved_threads {
+        switches.push((
+            queries.delete_thread_messages.clone(),
+            Box::new((thread_id,)),
+        ));
+    }
+
+    batch_in_chunks(state.clone(), &switches).await?;
+
+    // Removed last so a failed attempt can still be retried from the old address
+    state
+        .database_session
+        .execute_single_page(&queries.delete_user, (email,), PagingState::start())
+        .await?;
+
+    Ok(item_ids)
+}
+
+async fn is_migration_claim(
+    state: Arc<AppState>,
+    new_email: &str,
+    password_hash: &str,
+) -> Result<bool, AppError> {
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_user_row,
+            (new_email,),
+            PagingState::start(),
+        )
+        .await?;
+
+    match returned_rows.into_rows_result()?.first_row::<UserRow>() {
+        Ok((claimed_hash, _, _, _, _)) => Ok(claimed_hash == password_hash),
+        Err(RowsEmpty) => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+type BoxedRow<'a> = Box<dyn SerializeRow + Send + Sync + 'a>;
+
+// Page sized, so a heavy account cannot push a single batch past the size limit
+const BATCH_CHUNK_SIZE: usize = 100;
+
+async fn batch_in_chunks(
+    state: Arc<AppState>,
+    statements: &[(PreparedStatement, BoxedRow<'_>)],
+) -> Result<(), AppError> {
+    for chunk in statements.chunks(BATCH_CHUNK_SIZE) {
+        let mut batch: Batch = Default::default();
+        let mut batch_values = Vec::new();
+
+        for (statement, values) in chunk {
+            batch.append_statement(statement.clone());
+            batch_values.push(values);
+        }
+
+        state.database_session.batch(&batch, &batch_values).await?;
+    }
+
+    Ok(())
+}
+
+async fn get_thread_messages(
+    state: Arc<AppState>,
+    thread_id: &Uuid,
+) -> Result<Vec<ThreadMessageRow>, AppError> {
+    let mut paging_state = PagingState::start();
+    let mut messages: Vec<ThreadMessageRow> = Vec::new();
+
+    loop {
+        let (query_result, paging_state_response) = state
+            .database_session
+            .execute_single_page(
+                &state.database_queries.boiler_swap.get_thread_messages,
+                (thread_id,),
+                paging_state,
+            )
+            .await?;
+
+        for row in query_result
+            .into_rows_result()?
+            .rows::<ThreadMessageRow>()?
+        {
+            messages.push(row?);
+        }
+
+        match paging_state_response.into_paging_control_flow() {
+            ControlFlow::Break(()) => {
+                break;
+            }
+            ControlFlow::Continue(new_paging_state) => paging_state = new_paging_state,
+        }
+    }
+
+    Ok(messages)
+}
+
+async fn get_user_favorites(
+    state: Arc<AppState>,
+    email: &str,
+) -> Result<Vec<UserFavoriteRow>, AppError> {
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_favorite_ids,
+            (email,),
+            fallback_page_state,
+        )
+        .await?;
+
+    let ids: Vec<Uuid> = returned_rows
+        .into_rows_result()?
+        .rows::<(Uuid,)>()?
+        .map(|row| row.map(|(id,)| id))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut favorites = Vec::new();
+
+    for item_id in ids {
+        let (returned_rows, _) = state
+            .database_session
+            .execute_single_page(
+                &state.database_queries.boiler_swap.get_user_favorite,
+                (&item_id, email),
+                PagingState::start(),
+            )
+            .await?;
+
+        match returned_rows
+            .into_rows_result()?
+            .first_row::<(String, Option<bool>)>()
+        {
+            Ok((title, notify)) => favorites.push((item_id, title, notify)),
+            Err(RowsEmpty) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok(favorites)
+}
+
+async fn get_user_saved_searches(
+    state: Arc<AppState>,
+    email: &str,
+) -> Result<Vec<UserSavedSearchRow>, AppError> {
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_saved_searches,
+            (email,),
+            fallback_page_state,
+        )
+        .await?;
+
+    Ok(returned_rows
+        .into_rows_result()?
+        .rows::<SavedSearchRow>()?
+        .map(|row| {
+            row.map(|(_, search_id, query, item_type, condition, location)| {
+                (search_id, query.to_string(), item_type, condition, location)
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?)
+}
+
+async fn get_user_thread_rows(
+    state: Arc<AppState>,
+    email: &str,
+) -> Result<Vec<UserThreadRow>, AppError> {
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_user_thread_rows,
+            (email,),
+            fallback_page_state,
+        )
+        .await?;
+
+    Ok(returned_rows
+        .into_rows_result()?
+        .rows::<UserThreadRow>()?
+        .collect::<Result<Vec<_>, _>>()?)
+}
+
+// Item deletes go through CDC, which clears Meilisearch, photos, reports and item favorites
+pub async fn delete_user_records(state: Arc<AppState>, email: &str) -> Result<Vec<Uuid>, AppError> {
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_owned_item_ids,
+            (email,),
+            fallback_page_state,
+        )
+        .await?;
+
+    let item_ids: Vec<Uuid> = returned_rows
+        .into_rows_result()?
+        .rows::<(Uuid,)>()?
+        .map(|row| row.map(|(id,)| id))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let live_item_ids: Vec<Uuid> = if item_ids.is_empty() {
+        Vec::new()
+    } else {
+        let fallback_page_state = PagingState::start();
+
+        let (returned_rows, _) = state
+            .database_session
+            .execute_single_page(
+                &state.database_queries.boiler_swap.get_expiration_dates,
+                (&item_ids,),
+                fallback_page_state,
+            )
+            .await?;
+
+        returned_rows
+            .into_rows_result()?
+            .rows::<(Uuid, NaiveDate)>()?
+            .map(|row| row.map(|(id, _)| id))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let passkeys = get_passkeys(state.clone(), email).await?;
+    let favorites = get_user_favorites(state.clone(), email).await?;
+    let searches = get_user_saved_searches(state.clone(), email).await?;
+    let threads = get_user_thread_rows(state.clone(), email).await?;
+
+    let queries = &state.database_queries.boiler_swap;
+    let mut statements: Vec<(PreparedStatement, BoxedRow)> = Vec::new();
+
+    for item_id in live_item_ids {
+        statements.push((queries.delete_item.clone(), Box::new((item_id,))));
+    }
+
+    for (item_id, _, _) in favorites {
+        statements.push((queries.delete_favorite.clone(), Box::new((email, item_id))));
+        statements.push((
+            queries.delete_item_favorite.clone(),
+            Box::new((item_id, email)),
+        ));
+    }
+
+    let campus = &state.config.website_specific.campuses.for_email(email)?.id;
+
+    for (search_id, _, item_type, _, _) in searches {
+        statements.push((
+            queries.delete_saved_search.clone(),
+            Box::new((email, search_id)),
+        ));
+        statements.push((
+            queries.delete_alert_search.clone(),
+            Box::new((
+                campus,
+                item_type.unwrap_or(saved_searches_by_type::ANY_ITEM_TYPE),
+                email,
+                search_id,
+            )),
+        ));
+    }
+
+    // Conversations go for both parties so nobody keeps messaging a deleted account
+    for (thread_id, _, other_email, _) in threads {
+        statements.push((
+            queries.delete_user_thread.clone(),
+            Box::new((email, thread_id)),
+        ));
+        statements.push((
+            queries.delete_user_thread.clone(),
+            Box::new((other_email, thread_id)),
+        ));
+        statements.push((
+            queries.delete_thread_messages.clone(),
+            Box::new((thread_id,)),
+        ));
+    }
+
+    for passkey in passkeys {
+        statements.push((
+            queries.delete_passkey.clone(),
+            Box::new((passkey.credential_id.clone(),)),
+        ));
+        statements.push((
+            queries.delete_user_passkey.clone(),
+            Box::new((email, passkey.credential_id)),
+        ));
+    }
+
+    // The owner index and the users row go last, so a retry after a failed chunk
+    // can still find the listings and the account
+    for item_id in &item_ids {
+        statements.push((
+            queries.delete_owned_item.clone(),
+            Box::new((email, *item_id)),
+        ));
+    }
+
+    batch_in_chunks(state.clone(), &statements).await?;
+
+    state
+        .database_session
+        .execute_single_page(&queries.delete_user, (email,), PagingState::start())
+        .await?;
+
+    Ok(item_ids)
+}
+
+pub async fn insert_user(
+    state: Arc<AppState>,
+    account: &RedisAccount,
+    website_path: &WebsitePath,
+) -> Result<(), AppError> {
+    is_this_for_swap(website_path);
+
+    let fallback_page_state = PagingState::start();
+
+    state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.insert_user,
+            (account.email.clone(), account.password_hash.clone(), false),
+            fallback_page_state,
+        )
+        .await?;
+
+    Ok(())
+}
+
+fn is_this_for_swap(website_path: &WebsitePath) {
+    match website_path {
+        WebsitePath::BoilerSwap => {}
+        WebsitePath::Photos => panic!("Photos not implemented"),
+        WebsitePath::Home => panic!("Home not implemented"),
+    }
+}
+
+pub async fn get_user_role(
+    state: Arc<AppState>,
+    email: &str,
+    website_path: &WebsitePath,
+) -> Result<Option<UserRole>, AppError> {
+    is_this_for_swap(website_path);
+
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.get_user_role,
+            (email,),
+            fallback_page_state,
+        )
+        .await?;
+
+    match returned_rows
+        .into_rows_result()?
+        .first_row::<(Option<i8>,)>()
+    {
+        Ok((role_i8,)) => Ok(Some(
+            UserRole::try_from(convert_i8_to_u8(&role_i8.unwrap_or(0))).unwrap_or(UserRole::User),
+        )),
+        Err(RowsEmpty) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub async fn update_role(
+    state: Arc<AppState>,
+    email: &str,
+    role: UserRole,
+    website_path: &WebsitePath,
+) -> Result<(), AppError> {
+    is_this_for_swap(website_path);
+
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.update_role,
+            (role as i8, email),
+            fallback_page_state,
+        )
+        .await?;
+
+    // The account may have been deleted since its role was read
+    if !was_applied(returned_rows.into_rows_result()?.first_row::<Row>()?) {
+        return Err(AppError::BadRequest("User not found".to_string()));
+    }
+
+    Ok(())
+}
+
+pub async fn check_lock(
+    state: Arc<AppState>,
+    email: &str,
+    website_path: &WebsitePath,
+) -> Result<Option<bool>, AppError> {
+    is_this_for_swap(website_path);
+
+    let fallback_page_state = PagingState::start();
+
+    let (returned_rows, _) = state
+        .database_session
+        .execute_single_page(
+            &state.database_queries.boiler_swap.check_lock,
+            (email,),
+            fallback_page_state,
+        )
+        .await?;
+
+    match returned_rows
+        .into_rows_result()?
+        .first_row::<(bool, Option<i8>)>()
+    {
+        Ok((locked, role_i8)) => Ok(Some(locked || is_banned(role_i8))),
+        Err(RowsEmpty) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// A ban locks the account without touching the stored flag, so lifting it cannot
+// unfreeze an account that is midway through a password reset
+fn is_banned(role_i8: Option<i8>) -> bool {
+    role_i8 == Some(UserRole::Banned as i8)
 }
 
 pub async fn update_lock(
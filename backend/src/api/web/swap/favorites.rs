@@ -0,0 +1,51 @@
+use super::database::{get_items_by_ids, insert_favorite, take_item_favorites};
+use crate::{AppError, AppState, WebsitePath, api::web::twofactor::send_email};
+use std::sync::Arc;
+use tracing::warn;
+use uuid::Uuid;
+
+pub async fn try_star_item(
+    state: Arc<AppState>,
+    email: &str,
+    item_id: &Uuid,
+    notify: bool,
+) -> Result<(), AppError> {
+    let campus = &state.config.website_specific.campuses.for_email(email)?.id;
+
+    let item = get_items_by_ids(state.clone(), vec![*item_id], Some(campus))
+        .await?
+        .pop()
+        .ok_or(AppError::BadRequest("Item not found".to_string()))?;
+
+    insert_favorite(state.clone(), email, item_id, &item.title, notify).await
+}
+
+pub async fn prune_favorites(state: Arc<AppState>, item_id: &Uuid) -> Result<(), AppError> {
+    for (email, title, notify) in take_item_favorites(state.clone(), item_id).await? {
+        if !notify.unwrap_or(false) {
+            continue;
+        }
+
+        let state = state.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = send_email(
+                state.clone(),
+                &email,
+                "Watchlist",
+                format!(
+                    "\"{}\" from your watchlist is no longer available.\n\nBrowse similar items at {}/{}/browse",
+                    title,
+                    state.config.server.svelte_url,
+                    WebsitePath::BoilerSwap.as_ref(),
+                ),
+            )
+            .await
+            {
+                warn!("Watchlist email failed: {}", e);
+            }
+        });
+    }
+
+    Ok(())
+}
@@ -1,12 +1,47 @@
-use super::{models::ItemPayload, redis::try_post_item, utilities::check_item};
-use crate::{AppError, AppState, RedisAction, WebsitePath, api::web::verify::check_token};
+use super::{
+    admin::{
+        check_admin, get_admin_user, get_moderation_items, list_admin_items, try_ban_user,
+        try_hide_item,
+    },
+    alerts::{try_save_search, unsubscribe},
+    catalog::{read_catalog, try_add_catalog_entry},
+    database::{
+        delete_favorite, delete_item, delete_saved_search, get_favorites, get_owned_items,
+        get_saved_searches, get_threads,
+    },
+    favorites::try_star_item,
+    messages::{forward_messages, try_create_thread, try_get_messages, try_send_message},
+    models::{
+        AdminItemsQuery, BanPayload, CatalogPayload, CatalogQuery, FavoritePayload, HiddenPayload,
+        ItemPayload, ItemStatusPayload, MessagePayload, ReportPayload, SavedSearchPayload,
+        SearchPayload,
+    },
+    photo::try_add_item_photo,
+    redis::try_post_item,
+    reminders::renew_from_link,
+    reports::try_report_item,
+    search::try_search,
+    utilities::{
+        check_item, try_delete_item, try_renew_item, try_update_item, try_update_item_status,
+    },
+};
+use crate::{
+    AppError, AppState, RedisAction, WebsitePath,
+    api::web::{
+        models::LinkToken,
+        utilities::get_hashed_ip,
+        verify::{check_token, verify_token},
+    },
+};
 use axum::{
     Json,
-    extract::State,
+    body::Bytes,
+    extract::{ConnectInfo, Path, Query, State, ws::WebSocketUpgrade},
     http::{StatusCode, header::HeaderMap},
     response::IntoResponse,
 };
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc};
+use uuid::Uuid;
 
 pub async fn post_item_handler(
     headers: HeaderMap,
@@ -33,3 +68,605 @@ pub async fn post_item_handler(
 
     Ok((StatusCode::OK).into_response())
 }
+
+pub async fn owned_items_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &WebsitePath::BoilerSwap,
+    )
+    .await?
+    .serialized_account;
+
+    Ok((
+        StatusCode::OK,
+        Json(get_owned_items(state.clone(), &email.expect("session creation faulty")).await?),
+    )
+        .into_response())
+}
+
+pub async fn delete_item_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Path(item_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &WebsitePath::BoilerSwap,
+    )
+    .await?
+    .serialized_account;
+
+    try_delete_item(
+        state.clone(),
+        &email.expect("session creation faulty"),
+        &item_id,
+    )
+    .await?;
+
+    Ok((StatusCode::OK).into_response())
+}
+
+pub async fn update_item_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Path(item_id): Path<Uuid>,
+    Json(payload): Json<ItemPayload>,
+) -> Result<impl IntoResponse, AppError> {
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &WebsitePath::BoilerSwap,
+    )
+    .await?
+    .serialized_account;
+
+    check_item(&payload)?;
+
+    try_update_item(
+        state.clone(),
+        &email.expect("session creation faulty"),
+        &item_id,
+        payload,
+    )
+    .await?;
+
+    Ok((StatusCode::OK).into_response())
+}
+
+pub async fn upload_item_photo_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Path(item_id): Path<Uuid>,
+    body: Bytes,
+) -> Result<impl IntoResponse, AppError> {
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &WebsitePath::BoilerSwap,
+    )
+    .await?
+    .serialized_account;
+
+    Ok((
+        StatusCode::OK,
+        try_add_item_photo(
+            state.clone(),
+            &email.expect("session creation faulty"),
+            &item_id,
+            body.to_vec(),
+        )
+        .await?
+        .to_string(),
+    )
+        .into_response())
+}
+
+pub async fn update_item_status_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Path(item_id): Path<Uuid>,
+    Json(payload): Json<ItemStatusPayload>,
+) -> Result<impl IntoResponse, AppError> {
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &WebsitePath::BoilerSwap,
+    )
+    .await?
+    .serialized_account;
+
+    try_update_item_status(
+        state.clone(),
+        &email.expect("session creation faulty"),
+        &item_id,
+        payload.status,
+    )
+    .await?;
+
+    Ok((StatusCode::OK).into_response())
+}
+
+pub async fn renew_item_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Path(item_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &WebsitePath::BoilerSwap,
+    )
+    .await?
+    .serialized_account;
+
+    Ok((
+        StatusCode::OK,
+        Json(
+            try_renew_item(
+                state.clone(),
+                &email.expect("session creation faulty"),
+                &item_id,
+            )
+            .await?,
+        ),
+    )
+        .into_response())
+}
+
+pub async fn create_thread_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Path(item_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &WebsitePath::BoilerSwap,
+    )
+    .await?
+    .serialized_account;
+
+    Ok((
+        StatusCode::OK,
+        try_create_thread(
+            state.clone(),
+            &email.expect("session creation faulty"),
+            &item_id,
+        )
+        .await?
+        .to_string(),
+    )
+        .into_response())
+}
+
+pub async fn threads_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &WebsitePath::BoilerSwap,
+    )
+    .await?
+    .serialized_account;
+
+    Ok((
+        StatusCode::OK,
+        Json(get_threads(state.clone(), &email.expect("session creation faulty")).await?),
+    )
+        .into_response())
+}
+
+pub async fn messages_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Path(thread_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &WebsitePath::BoilerSwap,
+    )
+    .await?
+    .serialized_account;
+
+    Ok((
+        StatusCode::OK,
+        Json(
+            try_get_messages(
+                state.clone(),
+                &email.expect("session creation faulty"),
+                &thread_id,
+            )
+            .await?,
+        ),
+    )
+        .into_response())
+}
+
+pub async fn send_message_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Path(thread_id): Path<Uuid>,
+    Json(payload): Json<MessagePayload>,
+) -> Result<impl IntoResponse, AppError> {
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &WebsitePath::BoilerSwap,
+    )
+    .await?
+    .serialized_account;
+
+    Ok((
+        StatusCode::OK,
+        Json(
+            try_send_message(
+                state.clone(),
+                &email.expect("session creation faulty"),
+                &thread_id,
+                &payload.body,
+            )
+            .await?,
+        ),
+    )
+        .into_response())
+}
+
+pub async fn messages_socket_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    socket: WebSocketUpgrade,
+) -> Result<impl IntoResponse, AppError> {
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &WebsitePath::BoilerSwap,
+    )
+    .await?
+    .serialized_account;
+
+    let email = email.expect("session creation faulty");
+
+    Ok(socket.on_upgrade(move |socket| forward_messages(state, socket, email)))
+}
+
+pub async fn save_search_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<SavedSearchPayload>,
+) -> Result<impl IntoResponse, AppError> {
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &WebsitePath::BoilerSwap,
+    )
+    .await?
+    .serialized_account;
+
+    Ok((
+        StatusCode::OK,
+        try_save_search(
+            state.clone(),
+            &email.expect("session creation faulty"),
+            payload,
+        )
+        .await?
+        .to_string(),
+    )
+        .into_response())
+}
+
+pub async fn saved_searches_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &WebsitePath::BoilerSwap,
+    )
+    .await?
+    .serialized_account;
+
+    Ok((
+        StatusCode::OK,
+        Json(get_saved_searches(state.clone(), &email.expect("session creation faulty")).await?),
+    )
+        .into_response())
+}
+
+pub async fn delete_saved_search_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Path(search_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &WebsitePath::BoilerSwap,
+    )
+    .await?
+    .serialized_account;
+
+    delete_saved_search(
+        state.clone(),
+        &email.expect("session creation faulty"),
+        &search_id,
+    )
+    .await?;
+
+    Ok((StatusCode::OK).into_response())
+}
+
+pub async fn unsubscribe_handler(
+    State(state): State<Arc<AppState>>,
+    Query(payload): Query<LinkToken>,
+) -> Result<impl IntoResponse, AppError> {
+    unsubscribe(state.clone(), &payload.token).await?;
+
+    Ok((StatusCode::OK, "Unsubscribed from this saved search").into_response())
+}
+
+pub async fn renew_link_handler(
+    State(state): State<Arc<AppState>>,
+    Query(payload): Query<LinkToken>,
+) -> Result<impl IntoResponse, AppError> {
+    let renewal = renew_from_link(state.clone(), &payload.token).await?;
+
+    Ok((
+        StatusCode::OK,
+        format!("Listing renewed until {}", renewal.expiration_date),
+    )
+        .into_response())
+}
+
+pub async fn search_handler(
+    headers: HeaderMap,
+    ConnectInfo(address): ConnectInfo<SocketAddr>,
+    State(state): State<Arc<AppState>>,
+    Query(payload): Query<SearchPayload>,
+) -> Result<impl IntoResponse, AppError> {
+    // Browsing is public, a session only narrows the results to the user's campus
+    let email = match verify_token(state.clone(), headers.clone(), &WebsitePath::BoilerSwap).await?
+    {
+        Some(verified_result) if verified_result.redis_action == RedisAction::Session => {
+            verified_result.serialized_account
+        }
+        _ => None,
+    };
+
+    let hashed_ip = get_hashed_ip(&headers, address.ip());
+
+    Ok((
+        StatusCode::OK,
+        Json(try_search(state.clone(), email.as_deref(), &hashed_ip, payload).await?),
+    )
+        .into_response())
+}
+
+pub async fn report_item_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Path(item_id): Path<Uuid>,
+    Json(payload): Json<ReportPayload>,
+) -> Result<impl IntoResponse, AppError> {
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &WebsitePath::BoilerSwap,
+    )
+    .await?
+    .serialized_account;
+
+    try_report_item(
+        state.clone(),
+        &email.expect("session creation faulty"),
+        &item_id,
+        payload.reason,
+    )
+    .await?;
+
+    Ok((StatusCode::OK).into_response())
+}
+
+pub async fn admin_queue_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    check_admin(state.clone(), headers.clone()).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(get_moderation_items(state.clone()).await?),
+    )
+        .into_response())
+}
+
+pub async fn admin_items_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Query(payload): Query<AdminItemsQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    check_admin(state.clone(), headers.clone()).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(list_admin_items(state.clone(), payload).await?),
+    )
+        .into_response())
+}
+
+pub async fn admin_hide_item_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Path(item_id): Path<Uuid>,
+    Json(payload): Json<HiddenPayload>,
+) -> Result<impl IntoResponse, AppError> {
+    check_admin(state.clone(), headers.clone()).await?;
+
+    try_hide_item(state.clone(), &item_id, payload.hidden).await?;
+
+    Ok((StatusCode::OK).into_response())
+}
+
+pub async fn admin_delete_item_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Path(item_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    check_admin(state.clone(), headers.clone()).await?;
+
+    delete_item(state.clone(), &item_id).await?;
+
+    Ok((StatusCode::OK).into_response())
+}
+
+pub async fn admin_user_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Path(email): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    check_admin(state.clone(), headers.clone()).await?;
+
+    Ok((
+        StatusCode::OK,
+        Json(get_admin_user(state.clone(), &email).await?),
+    )
+        .into_response())
+}
+
+pub async fn admin_ban_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Path(email): Path<String>,
+    Json(payload): Json<BanPayload>,
+) -> Result<impl IntoResponse, AppError> {
+    let admin_email = check_admin(state.clone(), headers.clone()).await?;
+
+    try_ban_user(state.clone(), &admin_email, &email, payload.banned).await?;
+
+    Ok((StatusCode::OK).into_response())
+}
+
+pub async fn catalog_handler(
+    State(state): State<Arc<AppState>>,
+    Query(payload): Query<CatalogQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let entries = read_catalog(&state.catalog).entries(payload.campus.as_deref());
+
+    Ok((StatusCode::OK, Json(entries)).into_response())
+}
+
+pub async fn campuses_handler(
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    Ok((
+        StatusCode::OK,
+        Json(state.config.website_specific.campuses.all()),
+    )
+        .into_response())
+}
+
+pub async fn admin_catalog_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<CatalogPayload>,
+) -> Result<impl IntoResponse, AppError> {
+    check_admin(state.clone(), headers.clone()).await?;
+
+    try_add_catalog_entry(state.clone(), payload).await?;
+
+    Ok((StatusCode::OK).into_response())
+}
+
+pub async fn star_item_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Path(item_id): Path<Uuid>,
+    Json(payload): Json<FavoritePayload>,
+) -> Result<impl IntoResponse, AppError> {
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &WebsitePath::BoilerSwap,
+    )
+    .await?
+    .serialized_account;
+
+    try_star_item(
+        state.clone(),
+        &email.expect("session creation faulty"),
+        &item_id,
+        payload.notify,
+    )
+    .await?;
+
+    Ok((StatusCode::OK).into_response())
+}
+
+pub async fn unstar_item_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+    Path(item_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &WebsitePath::BoilerSwap,
+    )
+    .await?
+    .serialized_account;
+
+    delete_favorite(
+        state.clone(),
+        &email.expect("session creation faulty"),
+        &item_id,
+    )
+    .await?;
+
+    Ok((StatusCode::OK).into_response())
+}
+
+pub async fn favorites_handler(
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let email = check_token(
+        state.clone(),
+        headers.clone(),
+        &[RedisAction::Session],
+        &WebsitePath::BoilerSwap,
+    )
+    .await?
+    .serialized_account;
+
+    Ok((
+        StatusCode::OK,
+        Json(get_favorites(state.clone(), &email.expect("session creation faulty")).await?),
+    )
+        .into_response())
+}
@@ -0,0 +1,155 @@
+use super::{
+    database::{get_item_owner, get_messages, get_thread_recipient, insert_message, insert_thread},
+    models::Message,
+    redis::publish_message,
+    utilities::validate_item_attribute,
+};
+use crate::{
+    AppError, AppState, RedisAction, WebsitePath,
+    api::web::locks::{increment_lock_key, is_redis_locked},
+};
+use axum::extract::ws::{Message as SocketMessage, WebSocket};
+use futures_util::StreamExt;
+use std::sync::Arc;
+use tracing::debug;
+use uuid::Uuid;
+
+pub async fn try_create_thread(
+    state: Arc<AppState>,
+    email: &str,
+    item_id: &Uuid,
+) -> Result<Uuid, AppError> {
+    let seller = get_item_owner(state.clone(), item_id)
+        .await?
+        .ok_or(AppError::Unauthorized("Unable to verify".to_string()))?;
+
+    if seller == email {
+        return Err(AppError::BadRequest("Cannot message yourself".to_string()));
+    }
+
+    let campuses = &state.config.website_specific.campuses;
+
+    if campuses.for_email(&seller)?.id != campuses.for_email(email)?.id {
+        return Err(AppError::Unauthorized("Unable to verify".to_string()));
+    }
+
+    let thread_id = Uuid::new_v5(item_id, email.as_bytes());
+
+    insert_thread(state.clone(), &thread_id, item_id, &seller, email).await?;
+
+    Ok(thread_id)
+}
+
+pub async fn try_get_messages(
+    state: Arc<AppState>,
+    email: &str,
+    thread_id: &Uuid,
+) -> Result<Vec<Message>, AppError> {
+    get_thread_recipient(state.clone(), email, thread_id)
+        .await?
+        .ok_or(AppError::Unauthorized("Unable to verify".to_string()))?;
+
+    get_messages(state.clone(), email, thread_id).await
+}
+
+pub async fn try_send_message(
+    state: Arc<AppState>,
+    email: &str,
+    thread_id: &Uuid,
+    body: &str,
+) -> Result<Message, AppError> {
+    validate_item_attribute(body).map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+    if is_redis_locked(
+        state.clone(),
+        WebsitePath::BoilerSwap.as_ref(),
+        RedisAction::LockedMessages.as_ref(),
+        email,
+        &state.config.website_specific.max_messages,
+    )
+    .await?
+    {
+        return Err(AppError::Unauthorized("Sent too many messages".to_string()));
+    }
+
+    let recipient = get_thread_recipient(state.clone(), email, thread_id)
+        .await?
+        .ok_or(AppError::Unauthorized("Unable to verify".to_string()))?;
+
+    increment_lock_key(
+        state.clone(),
+        WebsitePath::BoilerSwap.as_ref(),
+        RedisAction::LockedMessages.as_ref(),
+        email,
+        &state.config.website_specific.max_messages_duration_seconds,
+        &state.config.website_specific.max_messages,
+    )
+    .await?;
+
+    let (message_id, sent_at) = insert_message(state.clone(), thread_id, email, body).await?;
+
+    let mut message = Message {
+        thread_id: *thread_id,
+        message_id,
+        body: body.to_string(),
+        sent_at,
+        from_me: false,
+    };
+
+    publish_message(state.clone(), &recipient, &message).await?;
+
+    message.from_me = true;
+
+    publish_message(state.clone(), email, &message).await?;
+
+    Ok(message)
+}
+
+pub async fn forward_messages(state: Arc<AppState>, mut socket: WebSocket, email: String) {
+    let mut pubsub = match state.redis_client.get_async_pubsub().await {
+        Ok(pubsub) => pubsub,
+        Err(e) => {
+            debug!("Pubsub connection failed: {}", e);
+            return;
+        }
+    };
+
+    if let Err(e) = pubsub
+        .subscribe(format!(
+            "{}:{}:{}",
+            WebsitePath::BoilerSwap.as_ref(),
+            RedisAction::Message.as_ref(),
+            &email
+        ))
+        .await
+    {
+        debug!("Pubsub subscription failed: {}", e);
+        return;
+    }
+
+    let mut messages = pubsub.on_message();
+
+    loop {
+        tokio::select! {
+            published = messages.next() => {
+                let Some(published) = published else {
+                    break;
+                };
+
+                let Ok(payload) = published.get_payload::<String>() else {
+                    continue;
+                };
+
+                if socket.send(SocketMessage::Text(payload)).await.is_err() {
+                    break;
+                }
+            }
+            received = socket.recv() => {
+                match received {
+                    Some(Ok(SocketMessage::Close(_))) | Some(Err(_)) | None => break,
+                    _ => {}
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,15 @@
+pub mod admin;
+pub mod alerts;
+pub mod catalog;
 pub mod cdc;
 pub mod database;
+pub mod favorites;
 pub mod handlers;
+pub mod messages;
 pub mod models;
+pub mod photo;
 pub mod redis;
+pub mod reminders;
+pub mod reports;
+pub mod search;
 pub mod utilities;
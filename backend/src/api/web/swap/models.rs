@@ -1,102 +1,190 @@
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, Utc};
 use num_enum::TryFromPrimitive;
 use serde::{Deserialize, Serialize};
 use strum_macros::{AsRefStr, EnumString};
 use uuid::Uuid;
 
-#[derive(TryFromPrimitive, Serialize, Deserialize, EnumString, AsRefStr)]
+#[derive(
+    TryFromPrimitive, Serialize, Deserialize, EnumString, AsRefStr, Clone, Copy, PartialEq, Eq, Hash,
+)]
 #[repr(u8)]
-pub enum ItemType {
-    #[strum(serialize = "Furniture")]
-    Furniture = 0,
+#[serde(rename_all = "snake_case")]
+pub enum CatalogKind {
+    #[strum(serialize = "item_type")]
+    ItemType = 0,
+
+    #[strum(serialize = "condition")]
+    Condition = 1,
 
-    #[strum(serialize = "Electronics")]
-    Electronics = 1,
+    #[strum(serialize = "location")]
+    Location = 2,
 
-    #[strum(serialize = "Books")]
-    Books = 2,
+    #[strum(serialize = "emoji")]
+    Emoji = 3,
+}
 
-    #[strum(serialize = "Kitchen")]
-    Kitchen = 3,
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct Geo {
+    pub lat: f64,
+    pub lng: f64,
+}
 
-    #[strum(serialize = "Clothing")]
-    Clothing = 4,
+#[derive(Serialize, Clone)]
+pub struct CatalogEntry {
+    #[serde(skip)]
+    pub code: i8,
+    pub name: String,
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geo: Option<Geo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub campus: Option<String>,
+}
 
-    #[strum(serialize = "Other")]
-    Other = 5,
+#[derive(Deserialize)]
+pub struct CatalogPayload {
+    pub kind: CatalogKind,
+    pub name: String,
+    pub label: String,
+    pub geo: Option<Geo>,
+    pub campus: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct CatalogQuery {
+    pub campus: Option<String>,
+}
 
-    #[strum(serialize = "Decor")]
-    Decor = 6,
+pub struct ItemCodes {
+    pub item_type: i8,
+    pub condition: i8,
+    pub location: i8,
+    pub emoji: i8,
 }
 
 #[derive(TryFromPrimitive, Serialize, Deserialize, EnumString, AsRefStr)]
 #[repr(u8)]
-pub enum Condition {
-    #[strum(serialize = "Excellent")]
-    Excellent = 0,
+pub enum ItemStatus {
+    #[strum(serialize = "Available")]
+    Available = 0,
 
-    #[strum(serialize = "Good")]
-    Good = 1,
+    #[strum(serialize = "Reserved")]
+    Reserved = 1,
 
-    #[strum(serialize = "Fair")]
-    Fair = 2,
+    #[strum(serialize = "Claimed")]
+    Claimed = 2,
 }
 
 #[derive(TryFromPrimitive, Serialize, Deserialize, EnumString, AsRefStr)]
 #[repr(u8)]
-pub enum Location {
-    #[strum(serialize = "CaryQuadEast")]
-    CaryQuadEast = 0,
+pub enum ThreadRole {
+    #[strum(serialize = "Seller")]
+    Seller = 0,
 
-    #[strum(serialize = "WileyHall")]
-    WileyHall = 1,
+    #[strum(serialize = "Buyer")]
+    Buyer = 1,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ItemPayload {
+    pub item_type: String,
+    pub condition: String,
+    pub title: String,
+    pub description: String,
+    pub location: String,
+    pub emoji: String,
+}
+
+#[derive(TryFromPrimitive, Serialize, Deserialize, EnumString, AsRefStr)]
+#[repr(u8)]
+pub enum ReportReason {
+    #[strum(serialize = "Spam")]
+    Spam = 0,
 
-    #[strum(serialize = "HarrisonHall")]
-    HarrisonHall = 2,
+    #[strum(serialize = "Inappropriate")]
+    Inappropriate = 1,
 
-    #[strum(serialize = "EarhartHall")]
-    EarhartHall = 3,
+    #[strum(serialize = "Scam")]
+    Scam = 2,
 
-    #[strum(serialize = "HillenbrandHall")]
-    HillenbrandHall = 4,
+    #[strum(serialize = "Prohibited")]
+    Prohibited = 3,
 
-    #[strum(serialize = "ThirdStreetSuites")]
-    ThirdStreetSuites = 5,
+    #[strum(serialize = "Other")]
+    Other = 4,
 }
 
-#[derive(TryFromPrimitive, Serialize, Deserialize, EnumString, AsRefStr)]
+#[derive(TryFromPrimitive, Serialize, Deserialize, EnumString, AsRefStr, PartialEq)]
 #[repr(u8)]
-pub enum Emoji {
-    #[strum(serialize = "Chair")]
-    Chair = 0,
+pub enum UserRole {
+    #[strum(serialize = "User")]
+    User = 0,
 
-    #[strum(serialize = "Snowflake")]
-    Snowflake = 1,
+    #[strum(serialize = "Admin")]
+    Admin = 1,
 
-    #[strum(serialize = "Books")]
-    Books = 2,
+    #[strum(serialize = "Banned")]
+    Banned = 2,
+}
 
-    #[strum(serialize = "Pan")]
-    Pan = 3,
+#[derive(Deserialize)]
+pub struct HiddenPayload {
+    pub hidden: bool,
+}
 
-    #[strum(serialize = "Monitor")]
-    Monitor = 4,
+#[derive(Deserialize)]
+pub struct BanPayload {
+    pub banned: bool,
+}
 
-    #[strum(serialize = "Decor")]
-    Decor = 5,
+#[derive(Serialize)]
+pub struct ModerationItem {
+    #[serde(flatten)]
+    pub item: Item,
+    pub reports: i64,
+    pub queued_at: DateTime<Utc>,
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct ItemPayload {
-    pub item_type: ItemType,
-    pub condition: Condition,
-    pub title: String,
-    pub description: String,
-    pub location: Location,
-    pub emoji: Emoji,
+#[derive(Serialize)]
+pub struct AdminUser {
+    pub email: String,
+    pub role: String,
+    pub locked: bool,
+    pub posting_count: u8,
+    pub items: Vec<Item>,
 }
 
 #[derive(Serialize)]
+pub struct AdminItem {
+    #[serde(flatten)]
+    pub item: Item,
+    pub owner: Option<String>,
+    pub hidden: bool,
+}
+
+#[derive(Serialize)]
+pub struct AdminItems {
+    pub items: Vec<AdminItem>,
+    pub cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct AdminItemsQuery {
+    pub cursor: Option<String>,
+    pub query: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ReportPayload {
+    pub reason: ReportReason,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ItemStatusPayload {
+    pub status: ItemStatus,
+}
+
+#[derive(Serialize, Deserialize)]
 pub struct Item {
     pub item_id: Uuid,
     pub item_type: String,
@@ -106,13 +194,175 @@ pub struct Item {
     pub description: String,
     pub emoji: String,
     pub expiration_date: String,
+    pub photos: Vec<Uuid>,
+    pub status: String,
+    pub campus: String,
+    #[serde(rename = "_geo", skip_serializing_if = "Option::is_none")]
+    pub geo: Option<Geo>,
+}
+
+#[derive(Deserialize)]
+pub struct SearchPayload {
+    #[serde(default)]
+    pub query: String,
+    pub item_type: Option<String>,
+    pub condition: Option<String>,
+    pub location: Option<String>,
+    #[serde(default)]
+    pub offset: usize,
+    pub limit: Option<usize>,
+    pub near: Option<String>,
+    pub radius: Option<u32>,
+    pub campus: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SearchHighlight {
+    pub title: String,
+    pub description: String,
 }
 
-pub struct CronItem {
+#[derive(Deserialize)]
+pub struct SearchDocument {
+    #[serde(flatten)]
+    pub item: Item,
+    #[serde(rename = "_geoDistance")]
+    pub distance: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct SearchHit {
+    #[serde(flatten)]
+    pub item: Item,
+    #[serde(rename = "_formatted")]
+    pub formatted: Option<SearchHighlight>,
+    #[serde(rename = "_geoDistance", skip_serializing_if = "Option::is_none")]
+    pub distance: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct SearchResults {
+    pub hits: Vec<SearchHit>,
+    pub estimated_total_hits: usize,
+}
+
+#[derive(Deserialize)]
+pub struct SavedSearchPayload {
+    pub query: String,
+    pub item_type: Option<String>,
+    pub condition: Option<String>,
+    pub location: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct SavedSearch {
+    pub search_id: Uuid,
+    pub query: String,
+    pub item_type: Option<String>,
+    pub condition: Option<String>,
+    pub location: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ItemRenewal {
+    pub expiration_date: String,
+    pub renewals_remaining: u8,
+}
+
+#[derive(Deserialize)]
+pub struct FavoritePayload {
+    #[serde(default)]
+    pub notify: bool,
+}
+
+#[derive(Deserialize)]
+pub struct MessagePayload {
+    pub body: String,
+}
+
+#[derive(Serialize)]
+pub struct Thread {
+    pub thread_id: Uuid,
     pub item_id: Uuid,
-    pub expiration_date: NaiveDate,
+    pub role: String,
+}
+
+#[derive(Serialize)]
+pub struct Message {
+    pub thread_id: Uuid,
+    pub message_id: Uuid,
+    pub body: String,
+    pub sent_at: DateTime<Utc>,
+    pub from_me: bool,
 }
 
-pub type ItemRow<'a> = (Uuid, i8, &'a str, i8, i8, &'a str, i8, NaiveDate);
+pub type ItemRow<'a> = (
+    Uuid,
+    i8,
+    &'a str,
+    i8,
+    i8,
+    &'a str,
+    i8,
+    NaiveDate,
+    Option<Vec<Uuid>>,
+    Option<i8>,
+    Option<bool>,
+    Option<&'a str>,
+);
+
+pub type ReminderRow = (Uuid, String, NaiveDate, Option<String>, i8, Option<bool>);
+
+pub type ThreadRow = (Uuid, Uuid, i8);
+
+pub type AdminItemRow = (Uuid, Option<String>, Option<bool>);
+
+pub type UserThreadRow = (Uuid, Uuid, String, i8);
+
+pub type ThreadMessageRow = (DateTime<Utc>, Uuid, String, String, Option<i32>);
+
+pub type RenewalRow = (
+    i8,
+    String,
+    i8,
+    i8,
+    String,
+    i8,
+    NaiveDate,
+    String,
+    Option<Vec<Uuid>>,
+    Option<i8>,
+    Option<bool>,
+    Option<i8>,
+    Option<String>,
+);
+
+pub type FavoriteRow = (String, String, Option<bool>);
+
+pub type UserFavoriteRow = (Uuid, String, Option<bool>);
+
+pub type CatalogRow = (
+    i8,
+    i8,
+    String,
+    String,
+    Option<f64>,
+    Option<f64>,
+    Option<String>,
+);
+
+pub type ModerationRow = (Uuid, i64, DateTime<Utc>);
+
+pub type SavedSearchRow<'a> = (&'a str, Uuid, &'a str, Option<i8>, Option<i8>, Option<i8>);
+pub type UserSavedSearchRow = (Uuid, String, Option<i8>, Option<i8>, Option<i8>);
+pub type SavedSearchTtlRow<'a> = (
+    &'a str,
+    Uuid,
+    &'a str,
+    Option<i8>,
+    Option<i8>,
+    Option<i8>,
+    Option<i32>,
+);
 
-pub type CronItemRow<'a> = (Uuid, NaiveDate);
+pub type MessageRow<'a> = (DateTime<Utc>, Uuid, &'a str, &'a str);
@@ -0,0 +1,67 @@
+use super::{
+    database::{get_item_photo, get_item_photo_ids, insert_item_photo},
+    utilities::check_owner,
+};
+use crate::{AppError, AppState, api::bot::photo::resize_photo};
+use axum::{
+    extract::{Path, State},
+    http::{StatusCode, header::CONTENT_TYPE},
+    response::IntoResponse,
+};
+use image::{DynamicImage, GenericImageView, ImageFormat::Jpeg, ImageResult, load_from_memory};
+use std::{io::Cursor, sync::Arc};
+use tokio::task::spawn_blocking;
+use uuid::Uuid;
+
+pub async fn try_add_item_photo(
+    state: Arc<AppState>,
+    email: &str,
+    item_id: &Uuid,
+    photo_bytes: Vec<u8>,
+) -> Result<Uuid, AppError> {
+    check_owner(state.clone(), email, item_id).await?;
+
+    if photo_bytes.len() > state.config.website_specific.max_photo_bytes as usize {
+        return Err(AppError::BadRequest("Image too large".to_string()));
+    }
+
+    if get_item_photo_ids(state.clone(), item_id).await?.len()
+        >= state.config.website_specific.max_photos.into()
+    {
+        return Err(AppError::BadRequest("Too many photos".to_string()));
+    }
+
+    let max_dimension = state.config.website_specific.max_photo_dimension;
+
+    let processed = spawn_blocking(move || process_item_photo(&photo_bytes, max_dimension))
+        .await?
+        .map_err(|_| AppError::BadRequest("Invalid image".to_string()))?;
+
+    insert_item_photo(state.clone(), item_id, processed).await
+}
+
+fn process_item_photo(photo_bytes: &[u8], max_dimension: u32) -> ImageResult<Vec<u8>> {
+    let mut photo = load_from_memory(photo_bytes)?;
+
+    let (width, height) = photo.dimensions();
+
+    if width > max_dimension || height > max_dimension {
+        photo = resize_photo(&photo, max_dimension, max_dimension);
+    }
+
+    let mut bytes: Vec<u8> = Vec::new();
+
+    DynamicImage::ImageRgb8(photo.to_rgb8()).write_to(&mut Cursor::new(&mut bytes), Jpeg)?;
+
+    Ok(bytes)
+}
+
+pub async fn item_photo_handler(
+    State(state): State<Arc<AppState>>,
+    Path((item_id, photo_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, AppError> {
+    match get_item_photo(state.clone(), &item_id, &photo_id).await? {
+        Some(bytes) => Ok((StatusCode::OK, [(CONTENT_TYPE, "image/jpeg")], bytes).into_response()),
+        None => Ok((StatusCode::NOT_FOUND, "Not found").into_response()),
+    }
+}
@@ -1,4 +1,7 @@
-use super::{database::insert_item, models::ItemPayload};
+use super::{
+    database::insert_item,
+    models::{ItemPayload, Message},
+};
 use crate::{
     AppError, AppState, RedisAction, WebsitePath,
     api::{
@@ -7,7 +10,7 @@ use crate::{
     },
 };
 use once_cell::sync::Lazy;
-use redis::{Script, aio::ConnectionManager};
+use redis::{AsyncTypedCommands, Script, aio::ConnectionManager};
 use std::sync::Arc;
 
 static DECR_ITEMS_SCRIPT: Lazy<Script> = Lazy::new(|| {
@@ -33,7 +36,7 @@ async fn handle_item_insertion(
             "{}:{}:{}",
             website_path,
             RedisAction::DeletedItem.as_ref(),
-            &insert_item(state.clone(), item).await?.to_string()
+            &insert_item(state.clone(), item, email).await?.to_string()
         ),
         email,
         1_209_600,
@@ -94,3 +97,25 @@ pub async fn try_post_item(
 
     Ok(())
 }
+
+pub async fn publish_message(
+    state: Arc<AppState>,
+    email: &str,
+    message: &Message,
+) -> Result<(), AppError> {
+    state
+        .redis_connection_manager
+        .clone()
+        .publish(
+            format!(
+                "{}:{}:{}",
+                WebsitePath::BoilerSwap.as_ref(),
+                RedisAction::Message.as_ref(),
+                email
+            ),
+            serde_json::to_string(message)?,
+        )
+        .await?;
+
+    Ok(())
+}
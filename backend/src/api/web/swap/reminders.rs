@@ -0,0 +1,143 @@
+use super::{
+    database::renew_item,
+    models::{ItemRenewal, ItemStatus, ReminderRow},
+    utilities::check_owner,
+};
+use crate::{
+    AppError, AppState, WebsitePath,
+    api::web::{
+        models::{LinkAction, WebsiteRoute},
+        twofactor::send_email,
+        verify::{sign_link, verify_link},
+    },
+};
+use chrono::Utc;
+use scylla::response::PagingState;
+use std::{ops::ControlFlow, sync::Arc};
+use tokio_cron_scheduler::{Job, JobScheduler};
+use tracing::warn;
+use uuid::Uuid;
+
+pub async fn spawn_reminder_task(state: Arc<AppState>) -> Result<(), AppError> {
+    let scheduler = JobScheduler::new().await?;
+
+    scheduler
+        .add(Job::new_async("0 0 12 * * *", move |_uuid, _lock| {
+            let state = state.clone();
+
+            Box::pin(async move {
+                if let Err(e) = send_reminders(state).await {
+                    warn!("Sending expiration reminders failed: {}", e);
+                }
+            })
+        })?)
+        .await?;
+
+    tokio::spawn(async move {
+        scheduler.start().await.expect("Failed to start scheduler");
+    });
+
+    Ok(())
+}
+
+pub async fn renew_from_link(state: Arc<AppState>, token: &str) -> Result<ItemRenewal, AppError> {
+    let claims = verify_link(token, LinkAction::Renew)
+        .ok_or(AppError::Unauthorized("Unable to verify".to_string()))?;
+
+    check_owner(state.clone(), &claims.sub, &claims.id).await?;
+
+    renew_item(state.clone(), &claims.id).await
+}
+
+// Listings expiring today are removed by the next midnight sweep in expire_ttl
+async fn send_reminders(state: Arc<AppState>) -> Result<(), AppError> {
+    let mut paging_state = PagingState::start();
+    let today = Utc::now().date_naive();
+
+    loop {
+        let (query_result, paging_state_response) = state
+            .database_session
+            .execute_single_page(
+                &state.database_queries.boiler_swap.get_expiring_item_ids,
+                (today,),
+                paging_state,
+            )
+            .await?;
+
+        let ids: Vec<Uuid> = query_result
+            .into_rows_result()?
+            .rows::<(Uuid,)>()?
+            .map(|row| row.map(|(id,)| id))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if ids.is_empty() {
+            break Ok(());
+        }
+
+        let (returned_rows, _) = state
+            .database_session
+            .execute_single_page(
+                &state.database_queries.boiler_swap.get_reminder_items,
+                (ids,),
+                PagingState::start(),
+            )
+            .await?;
+
+        // Renewed items leave stale entries behind in today's bucket
+        let row_vec: Vec<ReminderRow> = returned_rows
+            .into_rows_result()?
+            .rows::<ReminderRow>()?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (item_id, title, expiration_date, owner, status, hidden) in row_vec {
+            if expiration_date != today
+                || hidden == Some(true)
+                || status == ItemStatus::Claimed as i8
+            {
+                continue;
+            }
+
+            if let Some(owner) = owner {
+                send_reminder(state.clone(), &owner, &item_id, &title).await?;
+            }
+        }
+
+        match paging_state_response.into_paging_control_flow() {
+            ControlFlow::Break(()) => {
+                break Ok(());
+            }
+            ControlFlow::Continue(new_paging_state) => paging_state = new_paging_state,
+        }
+    }
+}
+
+async fn send_reminder(
+    state: Arc<AppState>,
+    email: &str,
+    item_id: &Uuid,
+    title: &str,
+) -> Result<(), AppError> {
+    let renew_link = format!(
+        "{}/{}/{}/renew?token={}",
+        state.config.server.svelte_url,
+        WebsitePath::BoilerSwap.as_ref(),
+        WebsiteRoute::Links.as_ref(),
+        sign_link(email, LinkAction::Renew, item_id, 86_400)?
+    );
+
+    if let Err(e) = send_email(
+        state.clone(),
+        email,
+        "Listing Expiring",
+        format!(
+            "Your listing \"{}\" expires today and will be removed tonight.\n\nKeep it listed: {}",
+            title, renew_link
+        ),
+    )
+    .await
+    {
+        warn!("Reminder email failed: {}", e);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use super::{
+    database::{get_item_owner, hide_item, insert_report},
+    models::ReportReason,
+};
+use crate::{AppError, AppState};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub async fn try_report_item(
+    state: Arc<AppState>,
+    email: &str,
+    item_id: &Uuid,
+    reason: ReportReason,
+) -> Result<(), AppError> {
+    let owner = get_item_owner(state.clone(), item_id)
+        .await?
+        .ok_or(AppError::BadRequest("Item not found".to_string()))?;
+
+    if owner == email {
+        return Err(AppError::BadRequest(
+            "Cannot report your own item".to_string(),
+        ));
+    }
+
+    // Listings from other campuses are never shown, so their reports could only be abuse
+    let campuses = &state.config.website_specific.campuses;
+
+    if campuses.for_email(&owner)?.id != campuses.for_email(email)?.id {
+        return Err(AppError::BadRequest("Item not found".to_string()));
+    }
+
+    let reports = insert_report(state.clone(), item_id, email, reason).await?;
+
+    if reports >= state.config.website_specific.report_threshold.into() {
+        hide_item(state.clone(), item_id, reports).await?;
+    }
+
+    Ok(())
+}
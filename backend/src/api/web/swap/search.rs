@@ -0,0 +1,162 @@
+use super::{
+    catalog::{Catalog, read_catalog},
+    models::{
+        CatalogKind, SearchDocument, SearchHighlight, SearchHit, SearchPayload, SearchResults,
+    },
+};
+use crate::{
+    AppError, AppState, RedisAction, WebsitePath,
+    api::{
+        microservices::{
+            database::schema::{columns::boiler_swap::items, tables},
+            meilisearch::GEO,
+        },
+        web::{
+            locks::{increment_lock_key, is_redis_locked},
+            swap::models::ItemStatus,
+            verify::validate_length,
+        },
+    },
+};
+use chrono::Utc;
+use meilisearch_sdk::search::Selectors;
+use serde_json::Value;
+use std::sync::Arc;
+
+pub async fn try_search(
+    state: Arc<AppState>,
+    email: Option<&str>,
+    hashed_ip: &str,
+    payload: SearchPayload,
+) -> Result<SearchResults, AppError> {
+    if !validate_length(&payload.query) {
+        return Err(AppError::BadRequest("Too many chars".to_string()));
+    }
+
+    if is_redis_locked(
+        state.clone(),
+        WebsitePath::BoilerSwap.as_ref(),
+        RedisAction::LockedSearch.as_ref(),
+        hashed_ip,
+        &state.config.website_specific.max_searches,
+    )
+    .await?
+    {
+        return Err(AppError::Unauthorized(
+            "Too many requests from your ip".to_string(),
+        ));
+    }
+
+    increment_lock_key(
+        state.clone(),
+        WebsitePath::BoilerSwap.as_ref(),
+        RedisAction::LockedSearch.as_ref(),
+        hashed_ip,
+        &state.config.website_specific.max_searches_duration_seconds,
+        &state.config.website_specific.max_searches,
+    )
+    .await?;
+
+    let origin = match &payload.near {
+        Some(location) => Some(
+            read_catalog(&state.catalog)
+                .geo_by_name(location)
+                .ok_or(AppError::BadRequest("Invalid location".to_string()))?,
+        ),
+        None => None,
+    };
+
+    // Signed in users only see their own campus, visitors browse the campus they name
+    let campuses = &state.config.website_specific.campuses;
+    let campus = match email {
+        Some(email) => campuses
+            .by_email(email)
+            .ok_or(AppError::Unauthorized("Unable to verify".to_string()))?,
+        None => payload
+            .campus
+            .as_deref()
+            .and_then(|id| campuses.get(id))
+            .ok_or(AppError::BadRequest("Invalid campus".to_string()))?,
+    };
+
+    let mut filters = build_filters(&payload, &read_catalog(&state.catalog), &campus.id)
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+    if let (Some(geo), Some(radius)) = (origin, payload.radius) {
+        filters.push_str(&format!(
+            " AND {}Radius({}, {}, {})",
+            GEO, geo.lat, geo.lng, radius
+        ));
+    }
+
+    let sort = origin.map(|geo| format!("{}Point({}, {}):asc", GEO, geo.lat, geo.lng));
+    let sort_slice = sort.as_deref().map(|sort| [sort]);
+    let limit = payload
+        .limit
+        .unwrap_or(state.config.website_specific.max_search_page_size.into())
+        .min(state.config.website_specific.max_search_page_size.into());
+
+    let index = state.meili_client.index(tables::boiler_swap::ITEMS);
+
+    let mut query = index.search();
+
+    if let Some(sort) = &sort_slice {
+        query.with_sort(sort);
+    }
+
+    let results = query
+        .with_query(&payload.query)
+        .with_filter(&filters)
+        .with_limit(limit)
+        .with_offset(payload.offset)
+        .with_attributes_to_highlight(Selectors::Some(&[items::TITLE, items::DESCRIPTION]))
+        .with_highlight_pre_tag("<mark>")
+        .with_highlight_post_tag("</mark>")
+        .execute::<SearchDocument>()
+        .await?;
+
+    Ok(SearchResults {
+        estimated_total_hits: results.estimated_total_hits.unwrap_or(results.hits.len()),
+        hits: results
+            .hits
+            .into_iter()
+            .map(|hit| SearchHit {
+                item: hit.result.item,
+                distance: hit.result.distance,
+                formatted: hit.formatted_result.and_then(|map| {
+                    serde_json::from_value::<SearchHighlight>(Value::Object(map)).ok()
+                }),
+            })
+            .collect(),
+    })
+}
+
+fn build_filters(
+    payload: &SearchPayload,
+    catalog: &Catalog,
+    campus: &str,
+) -> Result<String, &'static str> {
+    let mut filters = vec![
+        format!("{} = \"{}\"", items::CAMPUS, campus),
+        format!(
+            "{} >= \"{}\"",
+            items::EXPIRATION_DATE,
+            Utc::now().date_naive().format("%Y-%m-%d")
+        ),
+        format!("{} != \"{}\"", items::STATUS, ItemStatus::Claimed.as_ref()),
+    ];
+
+    for (value, kind, column) in [
+        (&payload.item_type, CatalogKind::ItemType, items::ITEM_TYPE),
+        (&payload.condition, CatalogKind::Condition, items::CONDITION),
+        (&payload.location, CatalogKind::Location, items::LOCATION),
+    ] {
+        if let Some(name) = value {
+            catalog.code(kind, name)?;
+
+            filters.push(format!("{} = \"{}\"", column, name));
+        }
+    }
+
+    Ok(filters.join(" AND "))
+}
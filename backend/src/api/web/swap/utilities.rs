@@ -1,6 +1,11 @@
-use super::models::ItemPayload;
-use crate::{AppError, api::web::verify::validate_length};
+use super::{
+    database::{delete_item, get_item_owner, renew_item, update_item, update_item_status},
+    models::{ItemPayload, ItemRenewal, ItemStatus},
+};
+use crate::{AppError, AppState, api::web::verify::validate_length};
 use rustrict::CensorStr;
+use std::sync::Arc;
+use uuid::Uuid;
 
 pub fn check_item(payload: &ItemPayload) -> Result<(), AppError> {
     validate_item(&payload.title, &payload.description)
@@ -28,3 +33,62 @@ pub fn validate_item_attribute(payload: &str) -> Result<(), &'static str> {
 
     Ok(())
 }
+
+pub async fn check_owner(
+    state: Arc<AppState>,
+    email: &str,
+    item_id: &Uuid,
+) -> Result<(), AppError> {
+    match get_item_owner(state.clone(), item_id).await? {
+        Some(owner) if owner == email => Ok(()),
+        _ => Err(AppError::Unauthorized("Unable to verify".to_string())),
+    }
+}
+
+pub async fn try_delete_item(
+    state: Arc<AppState>,
+    email: &str,
+    item_id: &Uuid,
+) -> Result<(), AppError> {
+    check_owner(state.clone(), email, item_id).await?;
+
+    delete_item(state.clone(), item_id).await
+}
+
+pub async fn try_update_item(
+    state: Arc<AppState>,
+    email: &str,
+    item_id: &Uuid,
+    payload: ItemPayload,
+) -> Result<(), AppError> {
+    check_owner(state.clone(), email, item_id).await?;
+
+    update_item(
+        state.clone(),
+        item_id,
+        payload,
+        &state.config.website_specific.campuses.for_email(email)?.id,
+    )
+    .await
+}
+
+pub async fn try_renew_item(
+    state: Arc<AppState>,
+    email: &str,
+    item_id: &Uuid,
+) -> Result<ItemRenewal, AppError> {
+    check_owner(state.clone(), email, item_id).await?;
+
+    renew_item(state.clone(), item_id).await
+}
+
+pub async fn try_update_item_status(
+    state: Arc<AppState>,
+    email: &str,
+    item_id: &Uuid,
+    status: ItemStatus,
+) -> Result<(), AppError> {
+    check_owner(state.clone(), email, item_id).await?;
+
+    update_item_status(state.clone(), item_id, status).await
+}
@@ -0,0 +1,247 @@
+use super::{
+    locks::is_temporarily_locked,
+    models::{RecoveryCodes, RedisAction, TotpEnrollment, WebsitePath},
+    swap::database::{get_totp, remove_recovery_code, update_totp},
+};
+use crate::{
+    AppError, AppState,
+    api::{
+        bot::photo::generate_qr_bytes,
+        microservices::redis::{insert_id, remove_id, try_get},
+    },
+};
+use base32::Alphabet;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use rand::{Rng, RngCore, thread_rng};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+const TOTP_PERIOD_SECONDS: u64 = 30;
+
+const TOTP_DRIFT_STEPS: u64 = 1;
+
+const TOTP_DIGITS: u32 = 6;
+
+const RECOVERY_CODES: usize = 10;
+
+pub const RECOVERY_CODE_LENGTH: usize = 10;
+
+const SECRET_ALPHABET: Alphabet = Alphabet::Rfc4648 { padding: false };
+
+pub fn generate_totp(secret: &[u8], step: u64) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+
+    format!(
+        "{:0width$}",
+        binary % 10u32.pow(TOTP_DIGITS),
+        width = TOTP_DIGITS as usize
+    )
+}
+
+pub fn verify_totp(secret: &str, code: &str) -> Option<u64> {
+    let secret = base32::decode(SECRET_ALPHABET, secret)?;
+    let current_step = Utc::now().timestamp() as u64 / TOTP_PERIOD_SECONDS;
+
+    (current_step.saturating_sub(TOTP_DRIFT_STEPS)..=current_step + TOTP_DRIFT_STEPS)
+        .find(|step| generate_totp(&secret, *step) == code)
+}
+
+// Steps accepted within the last lock window, whose replay locks may still be live
+pub fn totp_lock_keys(email: &str) -> Vec<String> {
+    let current_step = Utc::now().timestamp() as u64 / TOTP_PERIOD_SECONDS;
+    let lock_steps = 2 * TOTP_DRIFT_STEPS + 1;
+
+    (current_step.saturating_sub(TOTP_DRIFT_STEPS + lock_steps)..=current_step + TOTP_DRIFT_STEPS)
+        .map(|step| {
+            format!(
+                "{}:{}:{}:{}",
+                WebsitePath::BoilerSwap.as_ref(),
+                RedisAction::LockedTotp.as_ref(),
+                email,
+                step
+            )
+        })
+        .collect()
+}
+
+pub fn hash_recovery_code(code: &str) -> String {
+    format!("{:x}", Sha256::digest(code.as_bytes()))
+}
+
+fn generate_recovery_codes() -> Vec<String> {
+    let mut rng = thread_rng();
+
+    (0..RECOVERY_CODES)
+        .map(|_| {
+            (0..RECOVERY_CODE_LENGTH)
+                .map(|_| char::from(b'0' + rng.gen_range(0..10)))
+                .collect()
+        })
+        .collect()
+}
+
+fn totp_uri(brand: &str, email: &str, secret: &str) -> String {
+    let issuer = brand.replace(' ', "%20");
+
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&digits={}&period={}",
+        issuer,
+        email.replace('@', "%40"),
+        secret,
+        issuer,
+        TOTP_DIGITS,
+        TOTP_PERIOD_SECONDS
+    )
+}
+
+fn pending_key(email: &str) -> String {
+    format!(
+        "{}:{}:{}",
+        WebsitePath::BoilerSwap.as_ref(),
+        RedisAction::TotpPending.as_ref(),
+        email
+    )
+}
+
+pub async fn start_enrollment(
+    state: Arc<AppState>,
+    email: &str,
+) -> Result<TotpEnrollment, AppError> {
+    if get_totp(state.clone(), email).await?.is_some() {
+        return Err(AppError::BadRequest(
+            "Authenticator already enabled".to_string(),
+        ));
+    }
+
+    let mut secret_bytes = [0u8; 20];
+    thread_rng().fill_bytes(&mut secret_bytes);
+    let secret = base32::encode(SECRET_ALPHABET, &secret_bytes);
+
+    insert_id(
+        state.clone(),
+        &pending_key(email),
+        &secret,
+        state
+            .config
+            .session
+            .temporary_session_duration_seconds
+            .into(),
+    )
+    .await?;
+
+    Ok(TotpEnrollment {
+        uri: totp_uri(
+            &state
+                .config
+                .website_specific
+                .campuses
+                .for_email(email)?
+                .brand,
+            email,
+            &secret,
+        ),
+        secret,
+    })
+}
+
+pub async fn enrollment_qr(state: Arc<AppState>, email: &str) -> Result<Vec<u8>, AppError> {
+    let secret = try_get(state.clone(), &pending_key(email))
+        .await?
+        .ok_or(AppError::BadRequest("No pending enrollment".to_string()))?;
+
+    generate_qr_bytes(&totp_uri(
+        &state
+            .config
+            .website_specific
+            .campuses
+            .for_email(email)?
+            .brand,
+        email,
+        &secret,
+    ))
+}
+
+pub async fn confirm_enrollment(
+    state: Arc<AppState>,
+    email: &str,
+    code: &str,
+) -> Result<RecoveryCodes, AppError> {
+    let secret = try_get(state.clone(), &pending_key(email))
+        .await?
+        .ok_or(AppError::BadRequest("No pending enrollment".to_string()))?;
+
+    if verify_totp(&secret, code).is_none() {
+        return Err(AppError::Unauthorized("Unable to verify".to_string()));
+    }
+
+    let recovery_codes = generate_recovery_codes();
+
+    update_totp(
+        state.clone(),
+        email,
+        Some(&secret),
+        Some(
+            recovery_codes
+                .iter()
+                .map(|code| hash_recovery_code(code))
+                .collect(),
+        ),
+    )
+    .await?;
+
+    remove_id(state.clone(), &pending_key(email)).await?;
+
+    Ok(RecoveryCodes { recovery_codes })
+}
+
+pub async fn disable_totp(state: Arc<AppState>, email: &str, code: &str) -> Result<(), AppError> {
+    if !verify_second_factor(state.clone(), email, code).await? {
+        return Err(AppError::Unauthorized("Unable to verify".to_string()));
+    }
+
+    update_totp(state.clone(), email, None, None).await
+}
+
+pub async fn verify_second_factor(
+    state: Arc<AppState>,
+    email: &str,
+    code: &str,
+) -> Result<bool, AppError> {
+    let Some((secret, recovery_codes)) = get_totp(state.clone(), email).await? else {
+        return Ok(false);
+    };
+
+    if let Some(step) = verify_totp(&secret, code) {
+        // A code stays valid across the drift window, so each step is only accepted once
+        return Ok(!is_temporarily_locked(
+            state.clone(),
+            WebsitePath::BoilerSwap.as_ref(),
+            RedisAction::LockedTotp.as_ref(),
+            &format!("{}:{}", email, step),
+            ((2 * TOTP_DRIFT_STEPS + 1) * TOTP_PERIOD_SECONDS) as i64,
+        )
+        .await?);
+    }
+
+    let hashed = hash_recovery_code(code);
+
+    if code.len() == RECOVERY_CODE_LENGTH && recovery_codes.contains(&hashed) {
+        remove_recovery_code(state.clone(), email, &hashed).await?;
+
+        return Ok(true);
+    }
+
+    Ok(false)
+}
@@ -22,12 +22,34 @@ async fn send_code_email(
     state: Arc<AppState>,
     user_email: &str,
     code: &str,
+    link: &Option<String>,
 ) -> Result<(), AppError> {
+    let body = match link {
+        Some(link) => format!("Your code is {}\n\nOr sign in directly: {}", code, link),
+        None => format!("Your code is {}", code),
+    };
+
+    send_email(state.clone(), user_email, "Code", body).await
+}
+
+pub async fn send_email(
+    state: Arc<AppState>,
+    user_email: &str,
+    subject: &str,
+    body: String,
+) -> Result<(), AppError> {
+    let brand = &state
+        .config
+        .website_specific
+        .campuses
+        .for_email(user_email)?
+        .brand;
+
     let email = Message::builder()
-        .from(format!("BoilerSwap <{}>", state.config.email.from_email).parse()?)
+        .from(format!("{} <{}>", brand, state.config.email.from_email).parse()?)
         .to(user_email.parse()?)
-        .subject("BoilerSwap Code")
-        .body(format!("Your code is {}", code))?;
+        .subject(format!("{} {}", brand, subject))
+        .body(body)?;
 
     let credentials = Credentials::new(
         state.config.email.from_email.to_string(),
@@ -48,6 +70,7 @@ pub fn spawn_code_task(
     state: Arc<AppState>,
     email: String,
     token: String,
+    link: Option<String>,
     forgot_key: Option<String>,
     website_path: WebsitePath,
 ) {
@@ -56,7 +79,7 @@ pub fn spawn_code_task(
             return;
         }
 
-        if let Err(error) = send_code_email(state.clone(), &email, &token).await {
+        if let Err(error) = send_code_email(state.clone(), &email, &token, &link).await {
             match error {
                 AppError::LettreAddress(msg) => debug!("Invalid email: {}", msg),
                 AppError::LettreTransport(msg) => debug!("Transport error: {}", msg),
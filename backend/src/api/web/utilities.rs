@@ -1,11 +1,17 @@
-use super::models::{RedisAction, VerifiedTokenResult, WebsitePath};
+use super::models::{RedisAction, SessionInfo, VerifiedTokenResult, WebsitePath};
 use crate::{
     AppError, AppState, WebsiteRoute, api::microservices::redis::try_get, metrics::incr_visitors,
 };
-use axum::{extract::Request, http::header::HeaderMap};
+use axum::{
+    extract::Request,
+    http::header::{HeaderMap, USER_AGENT},
+};
+use chrono::Utc;
 use sha2::{Digest, Sha256};
 use std::{net::IpAddr, sync::Arc};
 
+const MAX_USER_AGENT_CHARS: usize = 256;
+
 pub async fn clear_all_keys(
     state: Arc<AppState>,
     website_path: &str,
@@ -138,8 +144,8 @@ pub fn get_website_path(label: &str) -> WebsitePath {
     }
 }
 
-pub fn get_hashed_ip(headers: &HeaderMap, direct_ip: IpAddr) -> String {
-    let ip = headers
+fn get_client_ip(headers: &HeaderMap, direct_ip: IpAddr) -> String {
+    headers
         .get("cf-connecting-ip")
         .and_then(|v| v.to_str().ok())
         .map(|s| s.to_string())
@@ -149,14 +155,44 @@ pub fn get_hashed_ip(headers: &HeaderMap, direct_ip: IpAddr) -> String {
                 .and_then(|v| v.to_str().ok())
                 .and_then(|s| s.split(',').next().map(|s| s.trim().to_string()))
         })
-        .unwrap_or_else(|| direct_ip.to_string());
+        .unwrap_or_else(|| direct_ip.to_string())
+}
 
+pub fn get_hashed_ip(headers: &HeaderMap, direct_ip: IpAddr) -> String {
     let mut hasher = Sha256::new();
-    hasher.update(ip.as_bytes());
+    hasher.update(get_client_ip(headers, direct_ip).as_bytes());
 
     format!("{:x}", hasher.finalize())
 }
 
+// Only the network is kept, which is enough to recognise a device without storing the address
+fn approximate_ip(ip: &str) -> String {
+    match ip.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            let octets = ip.octets();
+            format!("{}.{}.{}.0", octets[0], octets[1], octets[2])
+        }
+        Ok(IpAddr::V6(ip)) => {
+            let segments = ip.segments();
+            format!("{:x}:{:x}:{:x}::", segments[0], segments[1], segments[2])
+        }
+        Err(_) => "Unknown".to_string(),
+    }
+}
+
+pub fn get_session_info(headers: &HeaderMap, direct_ip: IpAddr) -> SessionInfo {
+    SessionInfo {
+        created_at: Utc::now(),
+        user_agent: headers
+            .get(USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.chars().take(MAX_USER_AGENT_CHARS).collect())
+            .unwrap_or_else(|| "Unknown".to_string()),
+        ip: approximate_ip(&get_client_ip(headers, direct_ip)),
+        remember: false,
+    }
+}
+
 pub fn get_key(redis_action: RedisAction, hashed_ip: &str) -> String {
     format!("{}:{}", redis_action.as_ref(), hashed_ip)
 }
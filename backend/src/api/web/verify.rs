@@ -1,12 +1,13 @@
 use super::{
     cookies::get_cookie,
-    models::{Account, DummyClaims, VerifiedTokenResult},
+    models::{Account, DummyClaims, LinkAction, LinkClaims, VerifiedTokenResult},
+    totp::RECOVERY_CODE_LENGTH,
     twofactor::CODE_REGEX,
     utilities::{check_path, format_verified_result},
 };
 use crate::{
     AppError, AppState, RedisAction, WebsitePath,
-    config::{read_secret, try_load},
+    config::{Campuses, read_secret, try_load},
 };
 use argon2::{
     Algorithm::Argon2id, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier,
@@ -14,15 +15,20 @@ use argon2::{
 };
 use axum::{
     extract::Request,
-    http::header::{HeaderMap, ORIGIN},
+    http::{
+        Method,
+        header::{HeaderMap, ORIGIN},
+    },
 };
-use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode};
+use chrono::{Duration as chronoDuration, Utc};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
 use once_cell::sync::Lazy;
 use rand::rngs::OsRng;
 use regex::Regex;
 use std::sync::Arc;
+use uuid::Uuid;
 
-pub static EMAIL_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^.+@purdue\.edu$").unwrap());
+pub static EMAIL_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[^@\s]+@[^@\s]+$").unwrap());
 
 pub static VALIDATION: Lazy<Validation> = Lazy::new(|| Validation::new(Algorithm::HS256));
 
@@ -30,6 +36,19 @@ pub static SWAP_DECODING_KEY: Lazy<DecodingKey> = Lazy::new(|| read_decoding_key
 
 pub static HOME_DECODING_KEY: Lazy<DecodingKey> = Lazy::new(|| read_decoding_key("HOME_API_TOKEN"));
 
+pub static LINK_DECODING_KEY: Lazy<DecodingKey> =
+    Lazy::new(|| read_decoding_key("RUST_LINK_SECRET"));
+
+pub static LINK_ENCODING_KEY: Lazy<EncodingKey> = Lazy::new(|| {
+    EncodingKey::from_secret(
+        read_secret("RUST_LINK_SECRET")
+            .unwrap_or_else(|e| {
+                panic!("Failed to load RUST_LINK_SECRET: {}", e);
+            })
+            .as_bytes(),
+    )
+});
+
 pub static MAX_CHARS: Lazy<usize> = Lazy::new(|| try_load("PUBLIC_MAX_CHARS", "100").unwrap());
 
 pub static CODE_LENGTH: Lazy<usize> = Lazy::new(|| try_load("PUBLIC_CODE_LENGTH", "6").unwrap());
@@ -73,6 +92,31 @@ pub fn verify_api_token(headers: &HeaderMap, website_path: &WebsitePath) -> bool
     decode::<DummyClaims>(&jwt, decoding_key, &VALIDATION).is_ok()
 }
 
+pub fn sign_link(
+    email: &str,
+    action: LinkAction,
+    id: &Uuid,
+    ttl_seconds: i64,
+) -> Result<String, jsonwebtoken::errors::Error> {
+    encode(
+        &Header::default(),
+        &LinkClaims {
+            sub: email.to_string(),
+            action: action.as_ref().to_string(),
+            id: *id,
+            exp: (Utc::now() + chronoDuration::seconds(ttl_seconds)).timestamp() as usize,
+        },
+        &LINK_ENCODING_KEY,
+    )
+}
+
+pub fn verify_link(token: &str, action: LinkAction) -> Option<LinkClaims> {
+    decode::<LinkClaims>(token, &LINK_DECODING_KEY, &VALIDATION)
+        .ok()
+        .map(|data| data.claims)
+        .filter(|claims| claims.action == action.as_ref())
+}
+
 pub fn verify_password(password: &str, password_hash: &str) -> bool {
     let parsed_hash = PasswordHash::new(password_hash)
         .unwrap_or_else(|e| panic!("Failed to parse password hash: {}", e));
@@ -82,8 +126,12 @@ pub fn verify_password(password: &str, password_hash: &str) -> bool {
         .is_ok()
 }
 
-pub fn validate_account(email: &str, password: &str) -> Result<(), &'static str> {
-    validate_email(email)?;
+pub fn validate_account(
+    email: &str,
+    password: &str,
+    campuses: &Campuses,
+) -> Result<(), &'static str> {
+    validate_email(email, campuses)?;
 
     validate_password(password)?;
 
@@ -106,13 +154,13 @@ pub fn validate_password(password: &str) -> Result<(), &'static str> {
     Ok(())
 }
 
-pub fn validate_email(email: &str) -> Result<(), &'static str> {
+pub fn validate_email(email: &str, campuses: &Campuses) -> Result<(), &'static str> {
     if !validate_length(email) {
         return Err("Too many chars");
     }
 
-    if !EMAIL_REGEX.is_match(email) {
-        return Err("Email must be a Purdue address");
+    if !EMAIL_REGEX.is_match(email) || campuses.by_email(email).is_none() {
+        return Err("Email must be a campus address");
     }
 
     Ok(())
@@ -162,7 +210,16 @@ pub fn check_token_content(redis_action: &RedisAction, token: &str) -> Result<()
     match redis_action {
         RedisAction::Update => validate_password(token)
             .map_err(|_| AppError::Unauthorized("Unable to verify".to_string())),
-        RedisAction::Auth | RedisAction::Forgot
+        RedisAction::Auth
+            if (token.len() != *CODE_LENGTH && token.len() != RECOVERY_CODE_LENGTH)
+                || !CODE_REGEX.is_match(token) =>
+        {
+            Err(AppError::Unauthorized("Unable to verify".to_string()))
+        }
+        RedisAction::Forgot
+        | RedisAction::EmailOld
+        | RedisAction::EmailNew
+        | RedisAction::AccountDelete
             if token.len() != *CODE_LENGTH || !CODE_REGEX.is_match(token) =>
         {
             Err(AppError::Unauthorized("Unable to verify".to_string()))
@@ -171,17 +228,27 @@ pub fn check_token_content(redis_action: &RedisAction, token: &str) -> Result<()
     }
 }
 
+// Browsers omit Origin on same-origin GETs, so those fall back to Sec-Fetch-Site
+fn is_origin_allowed(headers: &HeaderMap, method: &Method, svelte_url: &str) -> bool {
+    if let Some(origin) = headers.get(ORIGIN) {
+        return origin.to_str().is_ok_and(|origin| origin == svelte_url);
+    }
+
+    if *method != Method::GET && *method != Method::HEAD {
+        return false;
+    }
+
+    headers
+        .get("sec-fetch-site")
+        .is_none_or(|site| site.to_str().is_ok_and(|site| site == "same-origin"))
+}
+
 pub async fn is_request_authorized(
     state: Arc<AppState>,
     headers: &HeaderMap,
     request: &mut Request,
 ) -> Result<(), AppError> {
-    let origin = headers
-        .get(ORIGIN)
-        .and_then(|value| value.to_str().ok())
-        .ok_or_else(|| AppError::Unauthorized("Unable to verify".to_string()))?;
-
-    if origin != state.config.server.svelte_url {
+    if !is_origin_allowed(headers, request.method(), &state.config.server.svelte_url) {
         return Err(AppError::Unauthorized("Unable to verify".to_string()));
     }
 
@@ -196,12 +263,12 @@ pub async fn is_request_authorized(
     Ok(())
 }
 
-pub fn check_email(token: &str) -> Result<(), AppError> {
-    validate_email(token).map_err(|e| AppError::BadRequest(e.to_string()))
+pub fn check_email(token: &str, campuses: &Campuses) -> Result<(), AppError> {
+    validate_email(token, campuses).map_err(|e| AppError::BadRequest(e.to_string()))
 }
 
-pub fn check_account(payload: &Account) -> Result<(), AppError> {
-    validate_account(&payload.email, &payload.password)
+pub fn check_account(payload: &Account, campuses: &Campuses) -> Result<(), AppError> {
+    validate_account(&payload.email, &payload.password, campuses)
         .map_err(|e| AppError::BadRequest(e.to_string()))
 }
 
@@ -1,5 +1,6 @@
 use crate::AppError;
-use std::{env, fmt::Display, fs::read_to_string, str::FromStr};
+use serde::Serialize;
+use std::{collections::HashMap, env, fmt::Display, fs::read_to_string, str::FromStr};
 use tracing::{info, warn};
 
 #[derive(Clone)]
@@ -30,12 +31,51 @@ pub struct Session {
     pub temporary_session_duration_seconds: u16,
     pub max_sessions: u8,
     pub session_duration_seconds: u16,
+    pub remember_session_duration_seconds: u32,
 }
 
 #[derive(Clone)]
 pub struct WebsiteSpecific {
     pub max_items: u8,
     pub home_limit_ms: u8,
+    pub max_photos: u8,
+    pub max_photo_bytes: u32,
+    pub max_photo_dimension: u32,
+    pub max_messages: u8,
+    pub max_messages_duration_seconds: u16,
+    pub max_saved_searches: u8,
+    pub alert_cooldown_seconds: u16,
+    pub max_search_page_size: u8,
+    pub max_searches: u8,
+    pub max_searches_duration_seconds: u16,
+    pub report_threshold: u8,
+    pub admin_emails: AdminEmails,
+    pub expiration_days: ExpirationPolicy,
+    pub max_renewals: u8,
+    pub campuses: Campuses,
+}
+
+// Tenants share the Redis namespace, the keyspace and the Meili index: rows and documents
+// carry the campus id and are filtered by it, and per-user Redis keys end in an email
+// whose domain already picks the campus
+#[derive(Clone, Serialize)]
+pub struct Campus {
+    pub id: String,
+    pub email_domain: String,
+    pub name: String,
+    pub brand: String,
+}
+
+#[derive(Clone)]
+pub struct Campuses(Vec<Campus>);
+
+#[derive(Clone)]
+pub struct AdminEmails(Vec<String>);
+
+#[derive(Clone)]
+pub struct ExpirationPolicy {
+    pub default_days: u16,
+    pub item_type_days: HashMap<String, u16>,
 }
 
 #[derive(Clone)]
@@ -103,6 +143,10 @@ impl Session {
             )?,
             max_sessions: try_load("RUST_MAX_SESSIONS", "2")?,
             session_duration_seconds: try_load("RUST_SESSION_DURATION_SECS", "3600")?,
+            remember_session_duration_seconds: try_load(
+                "RUST_REMEMBER_SESSION_DURATION_SECS",
+                "2592000",
+            )?,
         })
     }
 }
@@ -112,10 +156,154 @@ impl WebsiteSpecific {
         Ok(Self {
             home_limit_ms: try_load("RUST_HOME_LIMIT_MS", "50")?,
             max_items: try_load("RUST_MAX_ITEMS", "15")?,
+            max_photos: try_load("RUST_MAX_PHOTOS", "4")?,
+            max_photo_bytes: try_load("RUST_MAX_PHOTO_BYTES", "5242880")?,
+            max_photo_dimension: try_load("RUST_MAX_PHOTO_DIMENSION", "1024")?,
+            max_messages: try_load("RUST_MAX_MESSAGES", "30")?,
+            max_messages_duration_seconds: try_load("RUST_MAX_MESSAGES_DURATION_SECS", "60")?,
+            max_saved_searches: try_load("RUST_MAX_SAVED_SEARCHES", "5")?,
+            alert_cooldown_seconds: try_load("RUST_ALERT_COOLDOWN_SECS", "3600")?,
+            max_search_page_size: try_load("RUST_MAX_SEARCH_PAGE_SIZE", "20")?,
+            max_searches: try_load("RUST_MAX_SEARCHES", "60")?,
+            max_searches_duration_seconds: try_load("RUST_MAX_SEARCHES_DURATION_SECS", "60")?,
+            report_threshold: try_load("RUST_REPORT_THRESHOLD", "3")?,
+            admin_emails: try_load("RUST_ADMIN_EMAILS", "")?,
+            expiration_days: try_load("RUST_EXPIRATION_DAYS", "7")?,
+            max_renewals: try_load("RUST_MAX_RENEWALS", "2")?,
+            campuses: try_load(
+                "RUST_CAMPUSES",
+                "purdue:purdue.edu:Purdue University:BoilerSwap",
+            )?,
         })
     }
 }
 
+impl ExpirationPolicy {
+    pub fn days(&self, item_type: &str) -> u16 {
+        self.item_type_days
+            .get(item_type)
+            .copied()
+            .unwrap_or(self.default_days)
+    }
+}
+
+// "7,Furniture=14,Electronics=10" -> 7 days unless the item type overrides it
+impl FromStr for ExpirationPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut default_days = None;
+        let mut item_type_days = HashMap::new();
+
+        for entry in s
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+        {
+            match entry.split_once('=') {
+                Some((item_type, days)) => {
+                    item_type_days.insert(
+                        item_type.trim().to_string(),
+                        days.trim()
+                            .parse()
+                            .map_err(|e| format!("{}: {}", entry, e))?,
+                    );
+                }
+                None => {
+                    default_days = Some(entry.parse().map_err(|e| format!("{}: {}", entry, e))?)
+                }
+            }
+        }
+
+        Ok(Self {
+            default_days: default_days.ok_or("missing default days")?,
+            item_type_days,
+        })
+    }
+}
+
+impl Campuses {
+    pub fn all(&self) -> &[Campus] {
+        &self.0
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Campus> {
+        self.0.iter().find(|campus| campus.id == id)
+    }
+
+    pub fn by_email(&self, email: &str) -> Option<&Campus> {
+        let (_, domain) = email.rsplit_once('@')?;
+
+        self.0
+            .iter()
+            .find(|campus| campus.email_domain.eq_ignore_ascii_case(domain))
+    }
+
+    // Accounts are only created for configured domains, so a miss means the campus was
+    // removed from the config after sign up and its users must not land on another one
+    pub fn for_email(&self, email: &str) -> Result<&Campus, AppError> {
+        self.by_email(email)
+            .ok_or(AppError::Unauthorized("Unable to verify".to_string()))
+    }
+}
+
+// "purdue:purdue.edu:Purdue University:BoilerSwap,iu:iu.edu:Indiana University:HoosierSwap"
+impl FromStr for Campuses {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let campuses = s
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(
+                |entry| match entry.split(':').map(str::trim).collect::<Vec<_>>()[..] {
+                    [id, email_domain, name, brand]
+                        if !id.is_empty() && !email_domain.is_empty() =>
+                    {
+                        Ok(Campus {
+                            id: id.to_string(),
+                            email_domain: email_domain.to_string(),
+                            name: name.to_string(),
+                            brand: brand.to_string(),
+                        })
+                    }
+                    _ => Err(format!("{}: expected id:domain:name:brand", entry)),
+                },
+            )
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if campuses.is_empty() {
+            return Err("at least one campus is required".to_string());
+        }
+
+        Ok(Self(campuses))
+    }
+}
+
+impl AdminEmails {
+    pub fn contains(&self, email: &str) -> bool {
+        self.0
+            .iter()
+            .any(|admin_email| admin_email.eq_ignore_ascii_case(email))
+    }
+}
+
+// "alice@purdue.edu,bob@purdue.edu" -> accounts that are admins regardless of their stored role
+impl FromStr for AdminEmails {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(
+            s.split(',')
+                .map(str::trim)
+                .filter(|entry| !entry.is_empty())
+                .map(str::to_string)
+                .collect(),
+        ))
+    }
+}
+
 impl Bot {
     pub fn load() -> Result<Self, AppError> {
         Ok(Self {
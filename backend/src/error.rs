@@ -5,6 +5,7 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use image::ImageError;
+use jsonwebtoken::errors::Error as jsonwebtokenError;
 use lettre::{
     address::AddressError, error::Error as lettreGeneralError,
     transport::smtp::Error as lettreTransportError,
@@ -105,6 +106,9 @@ pub enum AppError {
     #[error("Reqwest error: {0}")]
     Reqwest(#[from] reqwestError),
 
+    #[error("Jsonwebtoken error: {0}")]
+    Jsonwebtoken(#[from] jsonwebtokenError),
+
     #[error("Invalid Credentials: {0}")]
     Unauthorized(String),
 
@@ -7,11 +7,34 @@ use crate::{
         },
         web::{
             handlers::{
-                api_token_check, authenticate_handler, delete_handler, forgot_handler,
-                resend_handler, verify_handler, visitors_handler,
+                api_token_check, authenticate_handler, change_email_handler,
+                change_password_handler, confirm_email_handler, delete_account_handler,
+                delete_handler, delete_passkey_handler, forgot_handler, magic_link_handler,
+                magic_link_page_handler, passkey_login_handler, passkey_login_options_handler,
+                passkey_register_handler, passkey_register_options_handler, passkeys_handler,
+                resend_handler, revoke_all_sessions_handler, revoke_session_handler,
+                sessions_handler, start_account_deletion_handler, totp_confirm_handler,
+                totp_disable_handler, totp_enroll_handler, totp_qr_handler, verify_handler,
+                visitors_handler,
             },
             models::{METRICS_ROUTE, RedisAction, WebsitePath, WebsiteRoute},
-            swap::handlers::post_item_handler,
+            swap::{
+                alerts::backfill_alert_index,
+                handlers::{
+                    admin_ban_handler, admin_catalog_handler, admin_delete_item_handler,
+                    admin_hide_item_handler, admin_items_handler, admin_queue_handler,
+                    admin_user_handler, campuses_handler, catalog_handler, create_thread_handler,
+                    delete_item_handler, delete_saved_search_handler, favorites_handler,
+                    messages_handler, messages_socket_handler, owned_items_handler,
+                    post_item_handler, renew_item_handler, renew_link_handler, report_item_handler,
+                    save_search_handler, saved_searches_handler, search_handler,
+                    send_message_handler, star_item_handler, threads_handler, unstar_item_handler,
+                    unsubscribe_handler, update_item_handler, update_item_status_handler,
+                    upload_item_photo_handler,
+                },
+                photo::item_photo_handler,
+                reminders::spawn_reminder_task,
+            },
         },
     },
     error::AppError,
@@ -21,9 +44,10 @@ use crate::{
 };
 use axum::{
     Router,
+    extract::DefaultBodyLimit,
     http::{Method, header::CONTENT_TYPE},
     middleware,
-    routing::{delete, get, post},
+    routing::{delete, get, post, put},
 };
 use std::{net::SocketAddr, time::Duration};
 use tokio::net::TcpListener;
@@ -52,6 +76,10 @@ async fn main() -> Result<(), AppError> {
 
     start_bot(state.clone()).await?;
 
+    spawn_reminder_task(state.clone()).await?;
+
+    backfill_alert_index(state.clone()).await?;
+
     info!("Server configuration");
     info!("rust_port = {}", state.config.server.rust_port);
     info!("svelte_url = {}", state.config.server.svelte_url);
@@ -61,10 +89,25 @@ async fn main() -> Result<(), AppError> {
         .allow_origin(AllowOrigin::predicate(move |origin, _req| {
             origin.as_bytes() == origin_state.config.server.svelte_url.as_bytes()
         }))
-        .allow_methods([Method::GET, Method::OPTIONS, Method::POST, Method::DELETE])
+        .allow_methods([
+            Method::GET,
+            Method::OPTIONS,
+            Method::POST,
+            Method::PUT,
+            Method::DELETE,
+        ])
         .allow_headers([CONTENT_TYPE])
         .max_age(Duration::from_secs(60 * 60));
 
+    let admin_router = Router::new()
+        .route("/queue", get(admin_queue_handler))
+        .route("/catalog", post(admin_catalog_handler))
+        .route("/items", get(admin_items_handler))
+        .route("/items/:id", delete(admin_delete_item_handler))
+        .route("/items/:id/hidden", put(admin_hide_item_handler))
+        .route("/users/:email", get(admin_user_handler))
+        .route("/users/:email/ban", put(admin_ban_handler));
+
     let app = Router::new()
         .route(
             &format!(
@@ -101,6 +144,51 @@ async fn main() -> Result<(), AppError> {
             ),
             delete(delete_handler),
         )
+        .route(
+            &format!(
+                "/{}/{}/{}",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref(),
+                WebsiteRoute::Sessions.as_ref()
+            ),
+            get(sessions_handler).delete(revoke_all_sessions_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/{}",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref(),
+                WebsiteRoute::Password.as_ref()
+            ),
+            put(change_password_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/{}",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref(),
+                WebsiteRoute::Email.as_ref()
+            ),
+            post(change_email_handler).put(confirm_email_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/{}",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref(),
+                WebsiteRoute::Account.as_ref()
+            ),
+            post(start_account_deletion_handler).delete(delete_account_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/{}/:id",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref(),
+                WebsiteRoute::Sessions.as_ref()
+            ),
+            delete(revoke_session_handler),
+        )
         .route(
             &format!(
                 "/{}/{}/{}",
@@ -118,6 +206,168 @@ async fn main() -> Result<(), AppError> {
             ),
             post(post_item_handler),
         )
+        .route(
+            &format!(
+                "/{}/{}/items/mine",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref()
+            ),
+            get(owned_items_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/items/:id",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref()
+            ),
+            delete(delete_item_handler).put(update_item_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/items/:id/status",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref()
+            ),
+            put(update_item_status_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/items/:id/renew",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref()
+            ),
+            post(renew_item_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/items/:id/threads",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref()
+            ),
+            post(create_thread_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/threads",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref()
+            ),
+            get(threads_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/threads/ws",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref()
+            ),
+            get(messages_socket_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/threads/:id/messages",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref()
+            ),
+            get(messages_handler).post(send_message_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/items/:id/reports",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref()
+            ),
+            post(report_item_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/items/:id/favorite",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref()
+            ),
+            put(star_item_handler).delete(unstar_item_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/favorites",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref()
+            ),
+            get(favorites_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/catalog",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref()
+            ),
+            get(catalog_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/campuses",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref()
+            ),
+            get(campuses_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/search",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref()
+            ),
+            get(search_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/searches",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref()
+            ),
+            get(saved_searches_handler).post(save_search_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/searches/:id",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref()
+            ),
+            delete(delete_saved_search_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/unsubscribe",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Links.as_ref()
+            ),
+            get(unsubscribe_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/renew",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Links.as_ref()
+            ),
+            get(renew_link_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/login",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Links.as_ref()
+            ),
+            get(magic_link_page_handler).post(magic_link_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/items/:id/photos",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref()
+            ),
+            post(upload_item_photo_handler).layer(DefaultBodyLimit::max(
+                state.config.website_specific.max_photo_bytes as usize,
+            )),
+        )
         .route(
             &format!(
                 "/{}/{}/{}",
@@ -127,11 +377,97 @@ async fn main() -> Result<(), AppError> {
             ),
             post(resend_handler),
         )
+        .route(
+            &format!(
+                "/{}/{}/{}",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref(),
+                WebsiteRoute::Totp.as_ref()
+            ),
+            post(totp_enroll_handler)
+                .put(totp_confirm_handler)
+                .delete(totp_disable_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/{}/qr",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref(),
+                WebsiteRoute::Totp.as_ref()
+            ),
+            get(totp_qr_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/{}",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref(),
+                WebsiteRoute::Passkeys.as_ref()
+            ),
+            get(passkeys_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/{}/:id",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref(),
+                WebsiteRoute::Passkeys.as_ref()
+            ),
+            delete(delete_passkey_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/{}/register/options",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref(),
+                WebsiteRoute::Passkeys.as_ref()
+            ),
+            post(passkey_register_options_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/{}/register",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref(),
+                WebsiteRoute::Passkeys.as_ref()
+            ),
+            post(passkey_register_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/{}/login/options",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref(),
+                WebsiteRoute::Passkeys.as_ref()
+            ),
+            post(passkey_login_options_handler),
+        )
+        .route(
+            &format!(
+                "/{}/{}/{}/login",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref(),
+                WebsiteRoute::Passkeys.as_ref()
+            ),
+            post(passkey_login_handler),
+        )
         .route(
             &format!("/{}/:id", WebsitePath::Photos.as_ref()),
             get(photo_handler),
         )
+        .route(
+            &format!("/{}/items/:item_id/:photo_id", WebsitePath::Photos.as_ref()),
+            get(item_photo_handler),
+        )
         .route(METRICS_ROUTE, get(metrics_handler))
+        .nest(
+            &format!(
+                "/{}/{}/admin",
+                WebsitePath::BoilerSwap.as_ref(),
+                WebsiteRoute::Api.as_ref()
+            ),
+            admin_router,
+        )
         .route_layer(middleware::from_fn_with_state(
             state.clone(),
             api_token_check,
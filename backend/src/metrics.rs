@@ -19,6 +19,12 @@ pub enum RedisMetricAction {
 
     #[strum(serialize = "items")]
     Items,
+
+    #[strum(serialize = "expired_items")]
+    ExpiredItems,
+
+    #[strum(serialize = "expiry_ms")]
+    ExpiryMillis,
 }
 
 static DECR_METRIC_SCRIPT: Lazy<Script> = Lazy::new(|| {
@@ -40,6 +46,16 @@ pub async fn metrics_handler(State(state): State<Arc<AppState>>) -> Result<Strin
     let swap_items = IntGauge::new("swap_items", "Total items on BoilerSwap").unwrap();
     let bot_visitors = IntCounter::new("bot_visitors", "Total visitors on BoilerCuts").unwrap();
     let home_visitors = IntCounter::new("home_visitors", "Total visitors on Home").unwrap();
+    let swap_expired_items = IntGauge::new(
+        "swap_expired_items",
+        "Items deleted by the last BoilerSwap expiry sweep",
+    )
+    .unwrap();
+    let swap_expiry_ms = IntGauge::new(
+        "swap_expiry_ms",
+        "Duration of the last BoilerSwap expiry sweep in milliseconds",
+    )
+    .unwrap();
 
     pull_metric(
         state.clone(),
@@ -70,9 +86,26 @@ pub async fn metrics_handler(State(state): State<Arc<AppState>>) -> Result<Strin
     )
     .await?;
 
+    set_metric(
+        state.clone(),
+        WebsitePath::BoilerSwap,
+        RedisMetricAction::ExpiredItems,
+        &swap_expired_items,
+    )
+    .await?;
+    set_metric(
+        state.clone(),
+        WebsitePath::BoilerSwap,
+        RedisMetricAction::ExpiryMillis,
+        &swap_expiry_ms,
+    )
+    .await?;
+
     registry.register(Box::new(swap_visitors))?;
     registry.register(Box::new(bot_visitors))?;
     registry.register(Box::new(home_visitors))?;
+    registry.register(Box::new(swap_expired_items))?;
+    registry.register(Box::new(swap_expiry_ms))?;
 
     let metric_families = registry.gather();
 
@@ -8,16 +8,19 @@ use crate::{
         },
         web::{
             models::{RedisAction, WebsitePath},
-            swap::cdc::{expire_ttl, spawn_ttl_task},
+            swap::{
+                catalog::{Catalog, load_catalog},
+                cdc::{backfill_expiration_index, expire_ttl, spawn_ttl_task},
+            },
         },
     },
     config::Config,
     metrics::{RedisMetricAction, set_redis_metric},
 };
 use meilisearch_sdk::client::Client;
-use redis::aio::ConnectionManager;
+use redis::{Client as RedisClient, aio::ConnectionManager};
 use scylla::client::session::Session;
-use std::sync::{Arc, atomic::Ordering::Relaxed};
+use std::sync::{Arc, RwLock, atomic::Ordering::Relaxed};
 use tokio::task::JoinHandle;
 
 pub struct AppState {
@@ -25,7 +28,9 @@ pub struct AppState {
     pub database_session: Arc<Session>,
     pub database_queries: DatabaseQueries,
     pub redis_connection_manager: ConnectionManager,
+    pub redis_client: RedisClient,
     pub meili_client: Arc<Client>,
+    pub catalog: Arc<RwLock<Catalog>>,
 }
 
 impl AppState {
@@ -33,14 +38,30 @@ impl AppState {
         let redis_future = init_redis();
 
         let (database_session, database_queries) = init_database().await?;
-        expire_ttl(database_session.clone(), &database_queries).await?;
-        let expire_ttl_future = spawn_ttl_task(database_session.clone(), &database_queries);
+        let (redis_client, redis_connection_manager) = redis_future.await?;
 
-        let meili_future = init_meilisearch(database_session.clone(), &database_queries);
+        backfill_expiration_index(database_session.clone(), &database_queries).await?;
+        expire_ttl(
+            database_session.clone(),
+            &database_queries,
+            redis_connection_manager.clone(),
+        )
+        .await?;
+        let expire_ttl_future = spawn_ttl_task(
+            database_session.clone(),
+            &database_queries,
+            redis_connection_manager.clone(),
+        );
+
+        let catalog = Arc::new(RwLock::new(
+            load_catalog(&database_session, &database_queries).await?,
+        ));
+
+        let meili_future =
+            init_meilisearch(database_session.clone(), &database_queries, catalog.clone());
 
         let config = Config::load()?;
 
-        let redis_connection_manager = redis_future.await?;
         expire_ttl_future.await?;
         let (meili_client, meili_reindex_future, item_counter) = meili_future.await?;
 
@@ -62,7 +83,9 @@ impl AppState {
                 database_session,
                 database_queries,
                 redis_connection_manager,
+                redis_client,
                 meili_client,
+                catalog,
             }),
             meili_reindex_future,
         ))
@@ -1,23 +1,7 @@
-import { PUBLIC_MEILI_URL, PUBLIC_MEILI_KEY, PUBLIC_PAGE_SIZE } from '$env/static/public'
-import { Meilisearch } from 'meilisearch'
-import {
-	ItemFields,
-	ItemsTableName,
-	type Condition,
-	type Item,
-	type ItemType,
-	type Location
-} from './models'
+import { PUBLIC_SWAP_BACKEND_PATH, PUBLIC_PAGE_SIZE, PUBLIC_CAMPUS } from '$env/static/public'
+import { ItemFields, type Condition, type Item, type ItemType, type Location } from './models'
 import { appState } from './AppState.svelte'
 
-const client = new Meilisearch({
-	host: PUBLIC_MEILI_URL,
-	apiKey: PUBLIC_MEILI_KEY,
-	requestInit: {
-		credentials: 'include'
-	}
-})
-
 export async function search(
 	query: string,
 	itemTypeFilter: ItemType | '',
@@ -25,28 +9,34 @@ export async function search(
 	conditionFilter: Condition | '',
 	offset: number
 ) {
-	const filters: string[] = []
+	const params = new URLSearchParams({
+		query: query,
+		offset: String(offset),
+		limit: PUBLIC_PAGE_SIZE,
+		campus: PUBLIC_CAMPUS
+	})
 
 	if (itemTypeFilter !== '') {
-		filters.push(`${ItemFields.ITEM_TYPE} = ${itemTypeFilter}`)
+		params.set(ItemFields.ITEM_TYPE, itemTypeFilter)
 	}
 
 	if (locationFilter !== '') {
-		filters.push(`${ItemFields.LOCATION} = ${locationFilter}`)
+		params.set(ItemFields.LOCATION, locationFilter)
 	}
 
 	if (conditionFilter !== '') {
-		filters.push(`${ItemFields.CONDITION} = ${conditionFilter}`)
+		params.set(ItemFields.CONDITION, conditionFilter)
 	}
 
-	const response = await client.index(ItemsTableName).search(query, {
-		filter: filters,
-		limit: Number(PUBLIC_PAGE_SIZE),
-		offset: offset,
-		attributesToHighlight: [ItemFields.TITLE, ItemFields.DESCRIPTION],
-		highlightPreTag: '<mark>',
-		highlightPostTag: '</mark>'
+	const response = await fetch(PUBLIC_SWAP_BACKEND_PATH + '/search?' + params.toString(), {
+		credentials: 'include'
 	})
 
-	appState.setQueryResults(response.hits as Item[], response.estimatedTotalHits)
+	if (!response.ok) {
+		throw new Error(`HTTP error! status: ${response.status}`)
+	}
+
+	const results = await response.json()
+
+	appState.setQueryResults(results.hits as Item[], results.estimated_total_hits)
 }